@@ -11,7 +11,11 @@ use crate::date::Date;
 /// Annotation type for task annotations.
 /// Each annotation in taskwarrior consists of a date and a description,
 /// the date is named "entry", the description "description" in the JSON export.
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, Eq, PartialEq)]
+///
+/// Annotations are ordered chronologically by their entry date, annotations with the same entry
+/// date by their description.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Annotation {
     entry: Date,
     description: String,
@@ -44,5 +48,35 @@ impl Annotation {
     }
 }
 
+impl From<(Date, String)> for Annotation {
+    fn from((entry, description): (Date, String)) -> Annotation {
+        Annotation::new(entry, description)
+    }
+}
+
+impl From<(Date, &str)> for Annotation {
+    fn from((entry, description): (Date, &str)) -> Annotation {
+        Annotation::new(entry, description.to_owned())
+    }
+}
+
 #[cfg(test)]
-mod test {}
+mod test {
+    use chrono::NaiveDateTime;
+
+    use crate::annotation::Annotation;
+    use crate::date::{Date, TASKWARRIOR_DATETIME_TEMPLATE};
+
+    fn mkannotation(entry: &str, description: &str) -> Annotation {
+        let n = NaiveDateTime::parse_from_str(entry, TASKWARRIOR_DATETIME_TEMPLATE);
+        Annotation::new(Date::from(n.unwrap()), description.to_owned())
+    }
+
+    #[test]
+    fn test_annotation_ord() {
+        let old = mkannotation("20150619T165438Z", "zzz");
+        let new = mkannotation("20150620T165438Z", "aaa");
+        assert!(old < new);
+        assert!(mkannotation("20150619T165438Z", "aaa") < old);
+    }
+}
@@ -0,0 +1,63 @@
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+
+//! Module containing the `Clock` trait, the source of the current time
+//!
+//! Methods of `Task` which depend on the current time use the `SystemClock`. Most of them have a
+//! variant taking a `Clock` or the time `now`, so they can be used with a `FixedClock` to get
+//! deterministic results, e.g. in tests.
+
+use chrono::Utc;
+
+use crate::date::Date;
+
+/// A source of the current time
+pub trait Clock {
+    /// Get the current time
+    fn now(&self) -> Date;
+}
+
+/// The clock of the system, in UTC
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Date {
+        Date::from(Utc::now().naive_utc())
+    }
+}
+
+/// A clock which always returns the same time
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FixedClock(Date);
+
+impl FixedClock {
+    /// Create a clock which is stopped at `now`
+    pub fn new(now: Date) -> Self {
+        FixedClock(now)
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> Date {
+        self.0.clone()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::clock::{Clock, FixedClock, SystemClock};
+    use crate::date::Date;
+
+    #[test]
+    fn test_fixed_clock() {
+        let now = Date::from_timestamp(1_434_732_878).unwrap();
+        let clock = FixedClock::new(now.clone());
+        assert_eq!(clock.now(), now);
+        assert_eq!(clock.now(), now);
+        assert!(SystemClock.now() > now);
+    }
+}
@@ -0,0 +1,166 @@
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+
+//! Module for exporting tasks as CSV, e.g. for spreadsheets
+//!
+//! This module is only available with the `csv` feature.
+
+use std::collections::BTreeSet;
+use std::io::Write;
+
+use crate::date::{Date, TASKWARRIOR_DATETIME_TEMPLATE};
+use crate::error::Error;
+use crate::status::TaskStatus;
+use crate::task::{Task, TaskWarriorVersion};
+
+/// The separator the tags of a task are joined with
+pub const TAG_SEPARATOR: &str = ",";
+
+/// The columns every CSV export starts with
+pub const COLUMNS: [&str; 9] = [
+    "id",
+    "uuid",
+    "status",
+    "description",
+    "project",
+    "priority",
+    "due",
+    "entry",
+    "tags",
+];
+
+/// Export tasks as CSV
+///
+/// Writes a header row and one row per task. After the common columns in `COLUMNS`, every UDA
+/// which is set on any of the tasks gets its own column, ordered by name. Empty cells mean that
+/// a field is not set.
+pub fn export_csv<V: TaskWarriorVersion, W: Write>(tasks: &[Task<V>], w: W) -> Result<(), Error> {
+    let udas: BTreeSet<&String> = tasks.iter().flat_map(|t| t.uda().keys()).collect();
+    let mut writer = ::csv::Writer::from_writer(w);
+
+    let header = COLUMNS
+        .iter()
+        .copied()
+        .chain(udas.iter().map(|s| s.as_str()));
+    writer.write_record(header).map_err(Error::from_csv)?;
+
+    for task in tasks {
+        let mut record = vec![
+            task.id().map(|id| id.to_string()).unwrap_or_default(),
+            task.uuid().to_string(),
+            status(task.status()).to_owned(),
+            task.description().clone(),
+            task.project().map(ToString::to_string).unwrap_or_default(),
+            task.priority().cloned().unwrap_or_default(),
+            task.due().map(format_date).unwrap_or_default(),
+            format_date(task.entry()),
+            task.tags()
+                .map(|t| t.join(TAG_SEPARATOR))
+                .unwrap_or_default(),
+        ];
+        record.extend(udas.iter().map(|name| {
+            task.uda()
+                .get(name.as_str())
+                .map(|v| v.to_string())
+                .unwrap_or_default()
+        }));
+        writer.write_record(&record).map_err(Error::from_csv)?;
+    }
+
+    writer.flush().map_err(Error::from)
+}
+
+fn format_date(date: &Date) -> String {
+    date.format(TASKWARRIOR_DATETIME_TEMPLATE).to_string()
+}
+
+fn status(status: &TaskStatus) -> &'static str {
+    match status {
+        TaskStatus::Pending => "pending",
+        TaskStatus::Deleted => "deleted",
+        TaskStatus::Completed => "completed",
+        TaskStatus::Waiting => "waiting",
+        TaskStatus::Recurring => "recurring",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::csv::{export_csv, COLUMNS};
+    use crate::import::import;
+    use crate::task::TW26;
+
+    const TASKS: &str = r#"
+[
+    {
+        "id": 1,
+        "description": "some description",
+        "entry": "20150619T165438Z",
+        "project": "someproject",
+        "status": "waiting",
+        "tags": ["some", "tags"],
+        "uuid": "8ca953d5-18b4-4eb9-bd56-18f2e5b752f0",
+        "due": "20160508T164007Z"
+    },
+    {
+        "id": 2,
+        "description": "another, test",
+        "entry": "20150623T181011Z",
+        "priority": "L",
+        "status": "pending",
+        "uuid": "54d49ffc-a06b-4dd8-b7d1-db5f50594312",
+        "estimate": 30
+    }
+]
+"#;
+
+    #[test]
+    fn test_export_csv() {
+        let tasks = import::<TW26, _>(TASKS.as_bytes()).unwrap();
+        let mut buf = Vec::new();
+        export_csv(&tasks, &mut buf).unwrap();
+
+        let mut reader = ::csv::Reader::from_reader(&buf[..]);
+        let header: Vec<String> = reader.headers().unwrap().iter().map(String::from).collect();
+        let mut expected_header: Vec<&str> = COLUMNS.to_vec();
+        expected_header.push("estimate");
+        assert_eq!(header, expected_header);
+
+        let rows: Vec<Vec<String>> = reader
+            .records()
+            .map(|r| r.unwrap().iter().map(String::from).collect())
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                vec![
+                    "1",
+                    "8ca953d5-18b4-4eb9-bd56-18f2e5b752f0",
+                    "waiting",
+                    "some description",
+                    "someproject",
+                    "",
+                    "20160508T164007Z",
+                    "20150619T165438Z",
+                    "some,tags",
+                    "",
+                ],
+                vec![
+                    "2",
+                    "54d49ffc-a06b-4dd8-b7d1-db5f50594312",
+                    "pending",
+                    "another, test",
+                    "",
+                    "L",
+                    "",
+                    "20150623T181011Z",
+                    "",
+                    "30",
+                ],
+            ]
+        );
+    }
+}
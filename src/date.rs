@@ -8,7 +8,7 @@
 
 use std::ops::{Deref, DerefMut};
 
-use chrono::NaiveDateTime;
+use chrono::{DateTime, Datelike, Days, NaiveDateTime, NaiveTime};
 use serde::de::Error as SerdeError;
 use serde::de::Visitor;
 use serde::Deserialize;
@@ -16,10 +16,26 @@ use serde::Deserializer;
 use serde::Serialize;
 use serde::Serializer;
 
+use crate::duration::TwDuration;
+
 /// Date is a NaiveDateTime-Wrapper object to be able to implement foreign traits on it
-#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+#[derive(Clone, Debug, Hash, Eq, PartialEq, Ord, PartialOrd)]
 pub struct Date(NaiveDateTime);
 
+impl Date {
+    /// Create a date from a Unix timestamp, i.e. the seconds since 1970-01-01T00:00:00Z
+    ///
+    /// Returns `None` if the timestamp is out of the range chrono supports.
+    pub fn from_timestamp(secs: i64) -> Option<Date> {
+        DateTime::from_timestamp(secs, 0).map(|dt| Date(dt.naive_utc()))
+    }
+
+    /// Get the date as Unix timestamp, i.e. the seconds since 1970-01-01T00:00:00Z
+    pub fn timestamp(&self) -> i64 {
+        self.0.and_utc().timestamp()
+    }
+}
+
 impl Deref for Date {
     type Target = NaiveDateTime;
 
@@ -40,6 +56,36 @@ impl From<NaiveDateTime> for Date {
     }
 }
 
+/// Compute the date a relative date spec refers to at the time `now`
+///
+/// A spec is a duration with an optional sign, like `+7d`, `3weeks` or `-1h`, or one of the
+/// named dates `now`, `tomorrow` (its start), `eod` (the end of the day) and `eow` (the end of the
+/// week, which ends on sunday). Returns `None` if the spec can not be parsed or the date is out of
+/// range.
+pub(crate) fn relative_date(spec: &str, now: &Date) -> Option<Date> {
+    let end_of_day = NaiveTime::from_hms_opt(23, 59, 59)?;
+    let spec = spec.trim().to_lowercase();
+    let date = match spec.as_str() {
+        "now" => now.0,
+        "tomorrow" => now.date().succ_opt()?.and_time(NaiveTime::MIN),
+        "eod" => now.date().and_time(end_of_day),
+        "eow" => {
+            let days_left = 6 - now.weekday().num_days_from_monday();
+            now.date()
+                .checked_add_days(Days::new(u64::from(days_left)))?
+                .and_time(end_of_day)
+        }
+        _ => {
+            let duration = match spec.strip_prefix('-') {
+                Some(rest) => -*rest.parse::<TwDuration>().ok()?,
+                None => *spec.trim_start_matches('+').parse::<TwDuration>().ok()?,
+            };
+            now.0.checked_add_signed(duration)?
+        }
+    };
+    Some(Date(date))
+}
+
 /// The date-time parsing template used to parse the date time data exported by taskwarrior.
 pub static TASKWARRIOR_DATETIME_TEMPLATE: &str = "%Y%m%dT%H%M%SZ";
 
@@ -84,3 +130,44 @@ impl<'de> Deserialize<'de> for Date {
         deserializer.deserialize_str(DateVisitor)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDateTime;
+
+    use crate::date::{relative_date, Date, TASKWARRIOR_DATETIME_TEMPLATE};
+
+    fn mkdate(s: &str) -> Date {
+        let n = NaiveDateTime::parse_from_str(s, TASKWARRIOR_DATETIME_TEMPLATE);
+        Date::from(n.unwrap())
+    }
+
+    #[test]
+    fn test_timestamp_roundtrip() {
+        let date = Date::from_timestamp(1434732878).unwrap();
+        let expected =
+            NaiveDateTime::parse_from_str("20150619T165438Z", TASKWARRIOR_DATETIME_TEMPLATE);
+        assert_eq!(date, Date::from(expected.unwrap()));
+        assert_eq!(date.timestamp(), 1434732878);
+
+        assert_eq!(Date::from_timestamp(-1).unwrap().timestamp(), -1);
+        assert_eq!(Date::from_timestamp(i64::MAX), None);
+    }
+
+    #[test]
+    fn test_relative_date() {
+        // A wednesday
+        let now = mkdate("20150617T165438Z");
+        let relative = |spec| relative_date(spec, &now);
+
+        assert_eq!(relative("+7d"), Some(mkdate("20150624T165438Z")));
+        assert_eq!(relative("2h"), Some(mkdate("20150617T185438Z")));
+        assert_eq!(relative("-1day"), Some(mkdate("20150616T165438Z")));
+        assert_eq!(relative("now"), Some(now.clone()));
+        assert_eq!(relative("tomorrow"), Some(mkdate("20150618T000000Z")));
+        assert_eq!(relative("eod"), Some(mkdate("20150617T235959Z")));
+        assert_eq!(relative("EOW"), Some(mkdate("20150621T235959Z")));
+        assert_eq!(relative("soon"), None);
+        assert_eq!(relative("+"), None);
+    }
+}
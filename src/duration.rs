@@ -0,0 +1,306 @@
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+
+//! Module containing the `TwDuration` type and the parser for taskwarrior durations
+
+use std::fmt;
+use std::ops::Deref;
+use std::str::FromStr;
+
+use chrono::Duration;
+use serde::de::Error as DeError;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::error::Error;
+
+/// A taskwarrior duration, like `PT1H`, `3days` or `2w`
+///
+/// Like taskwarrior does for durations, a month counts as 30 days and a year as 365 days.
+/// It is serialized in the ISO-8601 form.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TwDuration(Duration);
+
+impl Deref for TwDuration {
+    type Target = Duration;
+
+    fn deref(&self) -> &Duration {
+        &self.0
+    }
+}
+
+impl From<Duration> for TwDuration {
+    fn from(d: Duration) -> Self {
+        TwDuration(d)
+    }
+}
+
+impl From<TwDuration> for Duration {
+    fn from(d: TwDuration) -> Self {
+        d.0
+    }
+}
+
+impl FromStr for TwDuration {
+    type Err = Error;
+
+    /// Parse a taskwarrior duration
+    ///
+    /// Named forms like `daily` or `weekly`, numeric forms like `3d` or `2weeks` and ISO-8601
+    /// forms like `P1M` or `PT90M` are supported.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse(s)
+            .and_then(|parts| {
+                let days = i64::from(parts.months / 12) * DAYS_PER_YEAR
+                    + i64::from(parts.months % 12) * DAYS_PER_MONTH;
+                parts.fixed.checked_add(&Duration::try_days(days)?)
+            })
+            .map(TwDuration)
+            .ok_or_else(|| Error::InvalidDuration(s.to_owned()))
+    }
+}
+
+impl fmt::Display for TwDuration {
+    /// Format the duration in the ISO-8601 form taskwarrior exports, e.g. `P1DT2H`
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.0 < Duration::zero() {
+            write!(f, "-")?;
+        }
+        let secs = self.0.num_seconds().unsigned_abs();
+        let (days, secs) = (secs / 86400, secs % 86400);
+        let (hours, mins, secs) = (secs / 3600, secs % 3600 / 60, secs % 60);
+
+        write!(f, "P")?;
+        if days > 0 {
+            write!(f, "{}D", days)?;
+        }
+        if hours > 0 || mins > 0 || secs > 0 || days == 0 {
+            write!(f, "T")?;
+        }
+        if hours > 0 {
+            write!(f, "{}H", hours)?;
+        }
+        if mins > 0 {
+            write!(f, "{}M", mins)?;
+        }
+        if secs > 0 || (days == 0 && hours == 0 && mins == 0) {
+            write!(f, "{}S", secs)?;
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for TwDuration {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for TwDuration {
+    fn deserialize<D>(deserializer: D) -> Result<TwDuration, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(D::Error::custom)
+    }
+}
+
+const DAYS_PER_MONTH: i64 = 30;
+const DAYS_PER_YEAR: i64 = 365;
+
+/// A parsed duration, with months kept apart from the durations of fixed length
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Parts {
+    pub(crate) months: u32,
+    pub(crate) fixed: Duration,
+}
+
+impl Parts {
+    fn months(months: u32) -> Self {
+        Parts {
+            months,
+            fixed: Duration::zero(),
+        }
+    }
+
+    fn fixed(fixed: Duration) -> Self {
+        Parts { months: 0, fixed }
+    }
+
+    fn scale(self, n: u32) -> Option<Self> {
+        Some(Parts {
+            months: self.months.checked_mul(n)?,
+            fixed: self.fixed.checked_mul(i32::try_from(n).ok()?)?,
+        })
+    }
+}
+
+/// Parse a taskwarrior duration in named, numeric or ISO-8601 form
+pub(crate) fn parse(s: &str) -> Option<Parts> {
+    let lower = s.trim().to_lowercase();
+
+    if let Some(named) = parse_named(&lower) {
+        return Some(named);
+    }
+    if let Some(iso) = lower.strip_prefix('p') {
+        return parse_iso8601(iso);
+    }
+
+    let split = lower.find(|c: char| !c.is_ascii_digit())?;
+    let (number, unit) = lower.split_at(split);
+    let number = if number.is_empty() {
+        1
+    } else {
+        number.parse().ok()?
+    };
+    unit_of(unit)?.scale(number)
+}
+
+/// Named durations which are not a number followed by a unit
+fn parse_named(s: &str) -> Option<Parts> {
+    let p = match s {
+        "hourly" => Parts::fixed(Duration::hours(1)),
+        "daily" => Parts::fixed(Duration::days(1)),
+        "weekly" => Parts::fixed(Duration::weeks(1)),
+        "biweekly" | "fortnight" => Parts::fixed(Duration::weeks(2)),
+        "monthly" => Parts::months(1),
+        "bimonthly" => Parts::months(2),
+        "quarterly" => Parts::months(3),
+        "semiannual" => Parts::months(6),
+        "annual" | "yearly" => Parts::months(12),
+        "biannual" | "biyearly" => Parts::months(24),
+        _ => return None,
+    };
+    Some(p)
+}
+
+/// The duration of a single unit
+fn unit_of(unit: &str) -> Option<Parts> {
+    let p = match unit {
+        "s" | "sec" | "secs" | "second" | "seconds" => Parts::fixed(Duration::seconds(1)),
+        "min" | "mins" | "minute" | "minutes" => Parts::fixed(Duration::minutes(1)),
+        "h" | "hr" | "hrs" | "hour" | "hours" => Parts::fixed(Duration::hours(1)),
+        "d" | "day" | "days" => Parts::fixed(Duration::days(1)),
+        "w" | "wk" | "wks" | "week" | "weeks" => Parts::fixed(Duration::weeks(1)),
+        "mo" | "mth" | "mths" | "month" | "months" => Parts::months(1),
+        "q" | "qtr" | "qtrs" | "quarter" | "quarters" => Parts::months(3),
+        "y" | "yr" | "yrs" | "year" | "years" => Parts::months(12),
+        _ => return None,
+    };
+    Some(p)
+}
+
+/// Parse the part of an ISO-8601 duration after the `P`
+fn parse_iso8601(s: &str) -> Option<Parts> {
+    let mut parts = Parts::months(0);
+    let mut in_time = false;
+    let mut number = String::new();
+    let mut seen = Vec::new();
+
+    for c in s.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        if c == 't' && !in_time && number.is_empty() {
+            in_time = true;
+            continue;
+        }
+        let n: u32 = number.parse().ok()?;
+        number.clear();
+        // Every designator may appear only once
+        if seen.contains(&(in_time, c)) {
+            return None;
+        }
+        seen.push((in_time, c));
+        let fixed = match (in_time, c) {
+            (false, 'y') => {
+                parts.months = parts.months.checked_add(n.checked_mul(12)?)?;
+                continue;
+            }
+            (false, 'm') => {
+                parts.months = parts.months.checked_add(n)?;
+                continue;
+            }
+            (false, 'w') => Duration::try_weeks(n.into())?,
+            (false, 'd') => Duration::try_days(n.into())?,
+            (true, 'h') => Duration::try_hours(n.into())?,
+            (true, 'm') => Duration::try_minutes(n.into())?,
+            (true, 's') => Duration::try_seconds(n.into())?,
+            _ => return None,
+        };
+        parts.fixed = parts.fixed.checked_add(&fixed)?;
+    }
+
+    if seen.is_empty() || !number.is_empty() {
+        return None;
+    }
+    Some(parts)
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::Duration;
+
+    use crate::duration::TwDuration;
+    use crate::error::Error;
+
+    fn parse(s: &str) -> Duration {
+        *s.parse::<TwDuration>().unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse("2w"), Duration::days(14));
+        assert_eq!(parse("PT90M"), Duration::minutes(90));
+        assert_eq!(parse("PT1H"), Duration::hours(1));
+        assert_eq!(parse("3days"), Duration::days(3));
+        assert_eq!(parse("1mo"), Duration::days(30));
+        assert_eq!(parse("P1Y"), Duration::days(365));
+        assert_eq!(parse("P1Y1M"), Duration::days(395));
+        assert_eq!(parse("weekly"), Duration::days(7));
+        assert_eq!(parse("0d"), Duration::zero());
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        for s in [
+            "",
+            "2",
+            "3x",
+            "P",
+            "PT1Y",
+            "P1D2D",
+            "P4294967295W4294967295W4294967295W4294967295W",
+        ] {
+            let d = s.parse::<TwDuration>();
+            assert!(
+                matches!(d, Err(Error::InvalidDuration(ref d)) if d == s),
+                "{s:?} parsed as {d:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_display() {
+        let display = |d: Duration| TwDuration::from(d).to_string();
+        assert_eq!(display(Duration::minutes(90)), "PT1H30M");
+        assert_eq!(display(Duration::days(14)), "P14D");
+        assert_eq!(display(Duration::hours(26)), "P1DT2H");
+        assert_eq!(display(Duration::zero()), "PT0S");
+        assert_eq!(display(-Duration::seconds(5)), "-PT5S");
+    }
+
+    #[test]
+    fn test_serde() {
+        let d: TwDuration = serde_json::from_str("\"PT90M\"").unwrap();
+        assert_eq!(*d, Duration::minutes(90));
+        assert_eq!(serde_json::to_string(&d).unwrap(), "\"PT1H30M\"");
+    }
+}
@@ -7,23 +7,107 @@
 //! Definitions for error handling with failure
 
 /// Failure error kind type, defining error messages
+///
+/// New variants may be added in minor releases.
 #[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
 pub enum Error {
     /// Error kind indicating that the JSON parser failed
     #[error("Failed to create a Task from JSON")]
-    ParserError,
+    ParserError(#[source] serde_json::Error),
 
     /// Error kind indicating that the Reader failed to read something
     #[error("Failed to read tasks from a Reader")]
-    ReaderError,
+    ReaderError(#[source] std::io::Error),
 
     /// Error kind indicating that a call to the task warrior binary failed
     #[error("There was a problem while calling the external 'task' binary")]
     TaskCmdError,
 
+    /// Error kind indicating that the external 'task' binary exited unsuccessfully
+    #[error("The external 'task' binary exited unsuccessfully ({status}): {}", stderr.trim())]
+    TaskCmdFailed {
+        /// The exit status of the 'task' binary
+        status: std::process::ExitStatus,
+        /// Everything the 'task' binary wrote to stderr
+        stderr: String,
+    },
+
+    /// Error kind indicating that the output of the external 'task' binary could not be understood
+    #[error("Unexpected output from the external 'task' binary: {0:?}")]
+    UnexpectedOutput(String),
+
+    /// Error kind indicating that the input of a hook did not follow the hook protocol
+    #[error("Unexpected input for a taskwarrior hook: {0}")]
+    UnexpectedHookInput(&'static str),
+
+    /// Error kind indicating that a query for a single task returned several tasks
+    #[error("Expected a single task, but {0} tasks were returned")]
+    NotUnique(usize),
+
     /// Error kind indicating that a conversion to JSON failed
     #[error("A Task could not be converted to JSON")]
-    SerializeError,
+    SerializeError(#[source] serde_json::Error),
+
+    /// Error kind indicating that a line of line-delimited JSON could not be parsed
+    #[error("Failed to create a Task from JSON in line {line}")]
+    ParseAtLine {
+        /// The 1-based number of the offending line
+        line: usize,
+        /// The underlying parser error
+        #[source]
+        source: serde_json::Error,
+    },
+
+    /// Error kind indicating that a single task could not be parsed, with the beginning of the
+    /// offending input
+    #[error("Failed to create a Task from JSON {input:?}")]
+    ParseWithInput {
+        /// The input, cut after `MAX_INPUT_SNIPPET` characters and followed by `...` if it was
+        /// longer
+        input: String,
+        /// The underlying parser error
+        #[source]
+        source: serde_json::Error,
+    },
+
+    /// Error kind indicating that writing CSV failed
+    #[cfg(feature = "csv")]
+    #[error("Failed to write tasks as CSV")]
+    CsvError(#[source] ::csv::Error),
+
+    /// Error kind indicating that a recurrence could not be parsed
+    #[error("Invalid recurrence: {0:?}")]
+    InvalidRecurrence(String),
+
+    /// Error kind indicating that a duration could not be parsed
+    #[error("Invalid duration: {0:?}")]
+    InvalidDuration(String),
+
+    /// Error kind indicating that a tag is not valid for taskwarrior
+    #[error("Invalid tag: {0:?}")]
+    InvalidTag(String),
+
+    /// Error kind indicating that a project is not valid for taskwarrior
+    #[error("Invalid project: {0:?}")]
+    InvalidProject(String),
+
+    /// Error kind indicating that a value of the wrong type was given for a task field
+    #[error("Invalid value for field {0:?}")]
+    InvalidFieldValue(String),
+
+    /// Error kind indicating that a recurrence mask contains other characters than `+-XW`
+    #[error("Invalid recurrence mask: {0:?}")]
+    InvalidMask(String),
+
+    /// Error kind indicating that an urgency is not a finite number
+    #[error("Invalid urgency: {0}")]
+    InvalidUrgency(f64),
+
+    /// Error kind indicating that the dependencies of tasks form a cycle, with the uuids of the
+    /// tasks in the cycle
+    #[error("Dependency cycle between tasks: {}", .0.iter().map(ToString::to_string).collect::<Vec<_>>().join(" -> "))]
+    DependencyCycle(Vec<uuid::Uuid>),
 
     /// Error wrapper for std::io::Error
     #[error(transparent)]
@@ -33,3 +117,76 @@ pub enum Error {
     #[error(transparent)]
     SerdeJson(#[from] serde_json::Error),
 }
+
+/// The number of characters of the input kept in `Error::ParseWithInput`
+pub const MAX_INPUT_SNIPPET: usize = 200;
+
+/// A violation of an invariant of taskwarrior, as reported by `Task::validate()`
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum ValidationError {
+    /// A completed or deleted task has no `end` date
+    #[error("A {0} task must have an end date")]
+    MissingEnd(crate::status::TaskStatus),
+
+    /// A waiting task has no `wait` date
+    #[error("A waiting task must have a wait date")]
+    MissingWait,
+
+    /// Only one of `until` and `recur` is set
+    #[error("The until and recur fields must be set together")]
+    UntilRecurMismatch,
+
+    /// A task depends on itself
+    #[error("A task must not depend on itself")]
+    SelfDependency,
+
+    /// The `due` date is before the `entry` date
+    #[error("The due date must not be before the entry date")]
+    DueBeforeEntry,
+}
+
+impl Error {
+    /// Map an error from reading JSON to a `ReaderError` or `ParserError`
+    pub(crate) fn from_deserialize(e: serde_json::Error) -> Error {
+        if e.is_io() {
+            Error::ReaderError(std::io::Error::from(e))
+        } else {
+            Error::ParserError(e)
+        }
+    }
+
+    /// Map an error from parsing `input` to a `ParseWithInput` carrying the start of the input
+    pub(crate) fn with_input(input: &str, source: serde_json::Error) -> Error {
+        let mut chars = input.chars();
+        let mut snippet: String = chars.by_ref().take(MAX_INPUT_SNIPPET).collect();
+        if chars.next().is_some() {
+            snippet.push_str("...");
+        }
+        Error::ParseWithInput {
+            input: snippet,
+            source,
+        }
+    }
+
+    /// Map an error from writing JSON to an `Io` error or `SerializeError`
+    pub(crate) fn from_serialize(e: serde_json::Error) -> Error {
+        if e.is_io() {
+            Error::Io(std::io::Error::from(e))
+        } else {
+            Error::SerializeError(e)
+        }
+    }
+
+    /// Map an error from writing CSV to an `Io` error or `CsvError`
+    #[cfg(feature = "csv")]
+    pub(crate) fn from_csv(e: ::csv::Error) -> Error {
+        if e.is_io_error() {
+            match e.into_kind() {
+                ::csv::ErrorKind::Io(e) => Error::Io(e),
+                _ => unreachable!(),
+            }
+        } else {
+            Error::CsvError(e)
+        }
+    }
+}
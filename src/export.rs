@@ -0,0 +1,363 @@
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+
+//! Module containing the `export()` function
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde_json;
+use serde_json::Value;
+
+use crate::error::Error;
+use crate::task::{Task, TaskWarriorVersion};
+
+/// Export tasks as taskwarrior-compatible JSON. This writes a JSON Array of objects, as expected
+/// by `task import`.
+pub fn export<T: TaskWarriorVersion, W: Write>(tasks: &[Task<T>], w: W) -> Result<(), Error> {
+    serde_json::to_writer(w, tasks).map_err(Error::from_serialize)
+}
+
+/// Export tasks like `export()`, after setting their `modified` date to the current time with
+/// `Task::touch()`
+pub fn export_touched<T: TaskWarriorVersion, W: Write>(
+    tasks: &mut [Task<T>],
+    w: W,
+) -> Result<(), Error> {
+    tasks.iter_mut().for_each(Task::touch);
+    export(tasks, w)
+}
+
+/// Export tasks as taskwarrior-compatible JSON to the file at the given path. The file is created
+/// if it does not exist and truncated if it does.
+pub fn export_to_path<T: TaskWarriorVersion, P: AsRef<Path>>(
+    tasks: &[Task<T>],
+    p: P,
+) -> Result<(), Error> {
+    let mut w = BufWriter::new(File::create(p)?);
+    export(tasks, &mut w)?;
+    w.flush().map_err(Error::from)
+}
+
+/// Export tasks as gzip-compressed taskwarrior-compatible JSON, the counterpart to
+/// `import_gz()`.
+///
+/// This function is only available with the `gzip` feature.
+#[cfg(feature = "gzip")]
+pub fn export_gz<T: TaskWarriorVersion, W: Write>(tasks: &[Task<T>], w: W) -> Result<(), Error> {
+    let mut encoder = flate2::write::GzEncoder::new(w, flate2::Compression::default());
+    export(tasks, &mut encoder)?;
+    encoder.finish()?;
+    Ok(())
+}
+
+/// Export tasks into multiple files in the directory `dir`, each with at most `max_per_file`
+/// tasks
+///
+/// The files are named `tasks-0001.json`, `tasks-0002.json` and so on, and each of them contains
+/// a JSON Array like the output of `export()`. Existing files are overwritten. The paths of the
+/// written files are returned, no file is written if there are no tasks.
+///
+/// # Panics
+///
+/// Panics if `max_per_file` is 0.
+pub fn export_sharded<T: TaskWarriorVersion, P: AsRef<Path>>(
+    tasks: &[Task<T>],
+    dir: P,
+    max_per_file: usize,
+) -> Result<Vec<PathBuf>, Error> {
+    assert!(max_per_file > 0, "max_per_file must not be 0");
+    tasks
+        .chunks(max_per_file)
+        .enumerate()
+        .map(|(i, shard)| {
+            let path = dir.as_ref().join(format!("tasks-{:04}.json", i + 1));
+            export_to_path(shard, &path)?;
+            Ok(path)
+        })
+        .collect()
+}
+
+/// The fields which come first in the output of `export_canonical()`, in this order
+pub const CANONICAL_FIELD_ORDER: [&str; 4] = ["uuid", "status", "entry", "description"];
+
+/// Export tasks as taskwarrior-compatible JSON with a canonical field order. This writes a JSON
+/// Array of objects, like `export()`.
+///
+/// The fields of each task are ordered as in `CANONICAL_FIELD_ORDER`, followed by all other
+/// fields, including the UDAs, ordered alphabetically. This gives byte-stable output which is
+/// suitable for fixtures and diffs.
+pub fn export_canonical<T: TaskWarriorVersion, W: Write>(
+    tasks: &[Task<T>],
+    mut w: W,
+) -> Result<(), Error> {
+    w.write_all(b"[")?;
+    for (i, task) in tasks.iter().enumerate() {
+        if i > 0 {
+            w.write_all(b",")?;
+        }
+        write_canonical(task, &mut w)?;
+    }
+    w.write_all(b"]")?;
+    Ok(())
+}
+
+fn write_canonical<T: TaskWarriorVersion, W: Write>(task: &Task<T>, mut w: W) -> Result<(), Error> {
+    let fields = match serde_json::to_value(task).map_err(Error::from_serialize)? {
+        Value::Object(fields) => fields,
+        _ => unreachable!("A Task is always serialized as JSON object"),
+    };
+    let rank = |key: &str| {
+        CANONICAL_FIELD_ORDER
+            .iter()
+            .position(|k| *k == key)
+            .unwrap_or(CANONICAL_FIELD_ORDER.len())
+    };
+    let mut fields: Vec<(&String, &Value)> = fields.iter().collect();
+    fields.sort_by(|(a, _), (b, _)| (rank(a), a).cmp(&(rank(b), b)));
+
+    w.write_all(b"{")?;
+    for (i, (key, value)) in fields.into_iter().enumerate() {
+        if i > 0 {
+            w.write_all(b",")?;
+        }
+        serde_json::to_writer(&mut w, key).map_err(Error::from_serialize)?;
+        w.write_all(b":")?;
+        serde_json::to_writer(&mut w, value).map_err(Error::from_serialize)?;
+    }
+    w.write_all(b"}")?;
+    Ok(())
+}
+
+/// Export a single Task as JSON-formatted String
+pub fn export_task<T: TaskWarriorVersion>(task: &Task<T>) -> Result<String, Error> {
+    serde_json::to_string(task).map_err(Error::from_serialize)
+}
+
+/// Writes one task-object per line, the counterpart to `import_tasks()`.
+pub fn export_tasks<T: TaskWarriorVersion, W: Write>(
+    tasks: &[Task<T>],
+    mut w: W,
+) -> Result<(), Error> {
+    for task in tasks {
+        serde_json::to_writer(&mut w, task).map_err(Error::from_serialize)?;
+        w.write_all(b"\n")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::BufReader;
+
+    use crate::error::Error;
+    use crate::export::{
+        export, export_canonical, export_sharded, export_task, export_tasks, export_to_path,
+        export_touched,
+    };
+    use crate::import::{import, import_from_path, import_task, import_tasks};
+    use crate::task::{Task, TW25, TW26};
+
+    const TASKS: &str = r#"
+[
+    {
+        "id": 1,
+        "description": "some description",
+        "entry": "20150619T165438Z",
+        "modified": "20160327T164007Z",
+        "project": "someproject",
+        "status": "waiting",
+        "tags": ["some", "tags", "are", "here"],
+        "uuid": "8ca953d5-18b4-4eb9-bd56-18f2e5b752f0",
+        "depends": ["54d49ffc-a06b-4dd8-b7d1-db5f50594312"],
+        "wait": "20160508T164007Z",
+        "urgency": 0.583562
+    },
+    {
+        "id": 2,
+        "description": "another test",
+        "entry": "20150623T181011Z",
+        "modified": "20160327T163718Z",
+        "priority": "L",
+        "project": "studying",
+        "status": "pending",
+        "uuid": "54d49ffc-a06b-4dd8-b7d1-db5f50594312",
+        "annotations": [
+            {
+                "entry": "20150623T181018Z",
+                "description": "fooooooobar"
+            }
+        ],
+        "estimate": "30",
+        "urgency": 3.16164
+    }
+]
+"#;
+
+    #[test]
+    fn test_export_roundtrip_tw26() {
+        let tasks = import::<TW26, _>(TASKS.as_bytes()).unwrap();
+
+        let mut buf = Vec::new();
+        export(&tasks, &mut buf).unwrap();
+        let reimported = import::<TW26, _>(&buf[..]).unwrap();
+
+        assert_eq!(tasks, reimported);
+    }
+
+    #[test]
+    fn test_export_roundtrip_tw25() {
+        let s = r#"
+[
+    {
+        "id": 1,
+        "description": "some description",
+        "entry": "20150619T165438Z",
+        "status": "pending",
+        "uuid": "8ca953d5-18b4-4eb9-bd56-18f2e5b752f0",
+        "depends": "54d49ffc-a06b-4dd8-b7d1-db5f50594312,08ee8dce-cb97-4c8c-9940-c9a440e90119"
+    }
+]
+"#;
+        let tasks = import::<TW25, _>(s.as_bytes()).unwrap();
+
+        let mut buf = Vec::new();
+        export(&tasks, &mut buf).unwrap();
+        let reimported = import::<TW25, _>(&buf[..]).unwrap();
+
+        assert_eq!(tasks, reimported);
+    }
+
+    #[test]
+    fn test_export_task_roundtrip() {
+        let tasks = import::<TW26, _>(TASKS.as_bytes()).unwrap();
+
+        for task in tasks {
+            let s = export_task(&task).unwrap();
+            assert!(!s.contains('\n'));
+            assert_eq!(task, import_task(&s).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_export_tasks_roundtrip() {
+        let tasks = import::<TW26, _>(TASKS.as_bytes()).unwrap();
+
+        let mut buf = Vec::new();
+        export_tasks(&tasks, &mut buf).unwrap();
+        assert_eq!(String::from_utf8_lossy(&buf).lines().count(), 2);
+
+        let reimported: Vec<Task<TW26>> = import_tasks(BufReader::new(&buf[..]))
+            .into_iter()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(tasks, reimported);
+    }
+
+    #[test]
+    fn test_export_to_path_roundtrip() {
+        let tasks = import::<TW26, _>(TASKS.as_bytes()).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tasks.json");
+
+        export_to_path(&tasks, &path).unwrap();
+        let reimported = import_from_path::<TW26, _>(&path).unwrap();
+
+        assert_eq!(tasks, reimported);
+    }
+
+    #[test]
+    fn test_export_sharded() {
+        let tasks: Vec<Task<TW26>> = (0..5)
+            .map(|i| {
+                Task::builder()
+                    .description(format!("task {}", i))
+                    .build()
+                    .unwrap()
+            })
+            .collect();
+        let dir = tempfile::tempdir().unwrap();
+
+        let paths = export_sharded(&tasks, dir.path(), 2).unwrap();
+        let names: Vec<_> = paths.iter().map(|p| p.file_name().unwrap()).collect();
+        assert_eq!(
+            names,
+            ["tasks-0001.json", "tasks-0002.json", "tasks-0003.json"]
+        );
+
+        let shards: Vec<Vec<Task<TW26>>> =
+            paths.iter().map(|p| import_from_path(p).unwrap()).collect();
+        let sizes: Vec<usize> = shards.iter().map(Vec::len).collect();
+        assert_eq!(sizes, [2, 2, 1]);
+        let descriptions: Vec<String> = shards
+            .concat()
+            .iter()
+            .map(|t| t.description().clone())
+            .collect();
+        let expected: Vec<String> = (0..5).map(|i| format!("task {}", i)).collect();
+        assert_eq!(descriptions, expected);
+    }
+
+    #[test]
+    fn test_import_from_missing_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let imported = import_from_path::<TW26, _>(dir.path().join("missing.json"));
+        assert!(matches!(imported, Err(Error::Io(_))));
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn test_export_gz_roundtrip() {
+        use crate::export::export_gz;
+        use crate::import::import_gz;
+
+        let tasks = import::<TW26, _>(TASKS.as_bytes()).unwrap();
+
+        let mut buf = Vec::new();
+        export_gz(&tasks, &mut buf).unwrap();
+        // gzip magic bytes
+        assert_eq!(&buf[..2], &[0x1f, 0x8b]);
+        let reimported = import_gz::<TW26, _>(&buf[..]).unwrap();
+
+        assert_eq!(tasks, reimported);
+    }
+
+    #[test]
+    fn test_export_canonical() {
+        let tasks = import::<TW26, _>(TASKS.as_bytes()).unwrap();
+
+        let mut buf = Vec::new();
+        export_canonical(&tasks, &mut buf).unwrap();
+        let s = String::from_utf8(buf).unwrap();
+        assert!(s.starts_with(
+            r#"[{"uuid":"8ca953d5-18b4-4eb9-bd56-18f2e5b752f0","status":"waiting","entry":"20150619T165438Z","description":"some description","depends":"#
+        ));
+        // The UDA is sorted in between the other fields
+        assert!(
+            s.contains(r#""entry":"20150623T181011Z","description":"another test","annotations":"#)
+        );
+        assert!(s.find(r#""estimate""#) < s.find(r#""modified":"20160327T163718Z""#));
+
+        assert_eq!(tasks, import::<TW26, _>(s.as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn test_export_touched() {
+        let mut tasks = import::<TW26, _>(TASKS.as_bytes()).unwrap();
+        let before = tasks.clone();
+
+        let mut buf = Vec::new();
+        export_touched(&mut tasks, &mut buf).unwrap();
+        let reimported = import::<TW26, _>(&buf[..]).unwrap();
+
+        for (old, new) in before.iter().zip(reimported.iter()) {
+            assert!(new.modified() > old.modified());
+            assert_eq!(new.entry(), old.entry());
+        }
+    }
+}
@@ -0,0 +1,114 @@
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+
+//! Module containing the `FieldValue` type, for accessing task fields by name
+
+use std::fmt;
+
+use uuid::Uuid;
+
+use crate::annotation::Annotation;
+use crate::date::{Date, TASKWARRIOR_DATETIME_TEMPLATE};
+use crate::mask::Mask;
+use crate::status::TaskStatus;
+use crate::tag::Tag;
+use crate::uda::UDAValue;
+use crate::urgency::Urgency;
+
+/// The value of a task field, as returned by `Task::get_field()` and taken by `Task::set_field()`
+#[derive(Clone, Debug, PartialEq)]
+pub enum FieldValue {
+    /// A string field, like `description` or `project`
+    Str(String),
+    /// An integer field, `id` or `imask`
+    U64(u64),
+    /// A date field, like `entry` or `due`
+    Date(Date),
+    /// A uuid field, `uuid` or `parent`
+    Uuid(Uuid),
+    /// The `depends` field
+    Uuids(Vec<Uuid>),
+    /// The `status` field
+    Status(TaskStatus),
+    /// The `tags` field
+    Tags(Vec<Tag>),
+    /// The `annotations` field
+    Annotations(Vec<Annotation>),
+    /// The `mask` field
+    Mask(Mask),
+    /// The `urgency` field
+    Urgency(Urgency),
+    /// A UDA
+    Uda(UDAValue),
+}
+
+impl fmt::Display for FieldValue {
+    /// Format the value like taskwarrior exports it, with lists separated by commas
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fn join<T: fmt::Display>(f: &mut fmt::Formatter, items: &[T]) -> fmt::Result {
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    f.write_str(",")?;
+                }
+                write!(f, "{}", item)?;
+            }
+            Ok(())
+        }
+
+        match self {
+            FieldValue::Str(s) => f.write_str(s),
+            FieldValue::U64(n) => write!(f, "{}", n),
+            FieldValue::Date(d) => write!(f, "{}", d.format(TASKWARRIOR_DATETIME_TEMPLATE)),
+            FieldValue::Uuid(u) => write!(f, "{}", u),
+            FieldValue::Uuids(u) => join(f, u),
+            FieldValue::Status(s) => write!(f, "{}", s),
+            FieldValue::Tags(t) => join(f, t),
+            FieldValue::Annotations(a) => {
+                let descriptions: Vec<&String> = a.iter().map(Annotation::description).collect();
+                join(f, &descriptions)
+            }
+            FieldValue::Mask(m) => write!(f, "{}", m),
+            FieldValue::Urgency(u) => write!(f, "{}", u),
+            FieldValue::Uda(u) => write!(f, "{}", u),
+        }
+    }
+}
+
+impl From<String> for FieldValue {
+    fn from(s: String) -> FieldValue {
+        FieldValue::Str(s)
+    }
+}
+
+impl From<&str> for FieldValue {
+    fn from(s: &str) -> FieldValue {
+        FieldValue::Str(s.to_owned())
+    }
+}
+
+impl From<Date> for FieldValue {
+    fn from(d: Date) -> FieldValue {
+        FieldValue::Date(d)
+    }
+}
+
+impl From<Uuid> for FieldValue {
+    fn from(u: Uuid) -> FieldValue {
+        FieldValue::Uuid(u)
+    }
+}
+
+impl From<TaskStatus> for FieldValue {
+    fn from(s: TaskStatus) -> FieldValue {
+        FieldValue::Status(s)
+    }
+}
+
+impl From<UDAValue> for FieldValue {
+    fn from(u: UDAValue) -> FieldValue {
+        FieldValue::Uda(u)
+    }
+}
@@ -0,0 +1,188 @@
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+
+//! Module containing the `Filter` type, for filtering tasks in memory
+//!
+//! This is not an implementation of the taskwarrior query syntax, but a programmatic predicate:
+//!
+//! ```
+//! use task_hookrs::filter::Filter;
+//! use task_hookrs::status::TaskStatus;
+//! # use task_hookrs::task::Task;
+//! # let tasks: Vec<Task> = Vec::new();
+//!
+//! let filter = Filter::new().status(TaskStatus::Pending).has_tag("work");
+//! let work: Vec<&Task> = tasks.iter().filter(|t| filter.matches(t)).collect();
+//! ```
+
+use crate::date::Date;
+use crate::status::TaskStatus;
+use crate::task::{Task, TaskWarriorVersion};
+
+/// A filter for tasks. A task matches if it matches all conditions, the empty filter matches
+/// every task.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Filter {
+    status: Option<TaskStatus>,
+    project: Option<String>,
+    tags: Vec<String>,
+    due_before: Option<Date>,
+}
+
+impl Filter {
+    /// Create a new filter, which matches every task
+    pub fn new() -> Self {
+        Filter::default()
+    }
+
+    /// Only match tasks with the given status
+    pub fn status(mut self, status: TaskStatus) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    /// Only match tasks in the given project or one of its subprojects, like `project:` does
+    pub fn project(mut self, project: &str) -> Self {
+        self.project = Some(project.to_owned());
+        self
+    }
+
+    /// Only match tasks with the given tag. If this is called several times, all tags are required.
+    pub fn has_tag(mut self, tag: &str) -> Self {
+        self.tags.push(tag.to_owned());
+        self
+    }
+
+    /// Only match tasks which are due before the given date
+    pub fn due_before(mut self, date: Date) -> Self {
+        self.due_before = Some(date);
+        self
+    }
+
+    /// Check whether the task matches all conditions of this filter
+    pub fn matches<V: TaskWarriorVersion>(&self, task: &Task<V>) -> bool {
+        if let Some(ref status) = self.status {
+            if task.status() != status {
+                return false;
+            }
+        }
+        if let Some(ref project) = self.project {
+            if !matches!(task.project(), Some(p) if p.is_within(project)) {
+                return false;
+            }
+        }
+        if let Some(ref date) = self.due_before {
+            if !matches!(task.due(), Some(due) if due < date) {
+                return false;
+            }
+        }
+        let tags: Vec<&str> = task
+            .tags()
+            .into_iter()
+            .flatten()
+            .map(|t| t.as_str())
+            .collect();
+        self.tags.iter().all(|tag| tags.contains(&tag.as_str()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDateTime;
+
+    use crate::date::{Date, TASKWARRIOR_DATETIME_TEMPLATE};
+    use crate::filter::Filter;
+    use crate::import::import;
+    use crate::status::TaskStatus;
+    use crate::task::{Task, TW26};
+
+    fn mkdate(s: &str) -> Date {
+        let n = NaiveDateTime::parse_from_str(s, TASKWARRIOR_DATETIME_TEMPLATE);
+        Date::from(n.unwrap())
+    }
+
+    fn tasks() -> Vec<Task<TW26>> {
+        let s = r#"
+[
+    {
+        "description": "one",
+        "entry": "20150619T165438Z",
+        "project": "work.meetings",
+        "status": "pending",
+        "tags": ["work", "urgent"],
+        "uuid": "8ca953d5-18b4-4eb9-bd56-18f2e5b752f0",
+        "due": "20160508T164007Z"
+    },
+    {
+        "description": "two",
+        "entry": "20150619T165438Z",
+        "project": "workshop",
+        "status": "pending",
+        "tags": ["work"],
+        "uuid": "54d49ffc-a06b-4dd8-b7d1-db5f50594312"
+    },
+    {
+        "description": "three",
+        "entry": "20150619T165438Z",
+        "end": "20150620T165438Z",
+        "project": "work",
+        "status": "completed",
+        "tags": ["work"],
+        "uuid": "08ee8dce-cb97-4c8c-9940-c9a440e90119"
+    }
+]
+"#;
+        import(s.as_bytes()).unwrap()
+    }
+
+    fn descriptions(filter: &Filter) -> Vec<String> {
+        tasks()
+            .iter()
+            .filter(|t| filter.matches(t))
+            .map(|t| t.description().clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_filter() {
+        assert_eq!(descriptions(&Filter::new()), ["one", "two", "three"]);
+        assert_eq!(
+            descriptions(&Filter::new().status(TaskStatus::Pending)),
+            ["one", "two"]
+        );
+        assert_eq!(
+            descriptions(
+                &Filter::new()
+                    .status(TaskStatus::Pending)
+                    .has_tag("work")
+                    .has_tag("urgent")
+            ),
+            ["one"]
+        );
+        assert!(descriptions(&Filter::new().has_tag("home")).is_empty());
+    }
+
+    #[test]
+    fn test_filter_project() {
+        assert_eq!(
+            descriptions(&Filter::new().project("work")),
+            ["one", "three"]
+        );
+        assert_eq!(
+            descriptions(&Filter::new().project("work.meetings")),
+            ["one"]
+        );
+    }
+
+    #[test]
+    fn test_filter_due_before() {
+        assert_eq!(
+            descriptions(&Filter::new().due_before(mkdate("20170101T000000Z"))),
+            ["one"]
+        );
+        assert!(descriptions(&Filter::new().due_before(mkdate("20160101T000000Z"))).is_empty());
+    }
+}
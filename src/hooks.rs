@@ -0,0 +1,326 @@
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+
+//! Module containing helpers for writing taskwarrior hooks
+//!
+//! Taskwarrior hooks get tasks as JSON lines on stdin and have to write the (possibly modified)
+//! task back to stdout as a single JSON line. All functions which use stdin, stdout or stderr have
+//! a `_from`/`_to` variant which works with any reader or writer instead.
+//!
+//! The `on-modify` hook gets the original and the modified task, and has to emit the final task
+//! with `emit_task()` just like the `on-add` hook.
+//!
+//! Taskwarrior passes information like the command and the data location to hooks as arguments,
+//! which `HookContext::from_env()` parses.
+//!
+//! To reject a task, a hook prints a message to stdout and exits unsuccessfully, which is what
+//! `reject()` does.
+//!
+//! An `on-add` hook which does not change anything looks like this:
+//!
+//! ```no_run
+//!   use task_hookrs::hooks::{emit_task, read_added_task};
+//!   use task_hookrs::task::{Task, TW26};
+//!
+//!   let task: Task<TW26> = read_added_task().unwrap();
+//!   emit_task(&task).unwrap();
+//! ```
+
+use std::env;
+use std::io::{stderr, stdin, stdout, BufRead, Write};
+use std::path::PathBuf;
+use std::process::exit;
+
+use serde_json;
+
+use crate::error::Error;
+use crate::import::import_task;
+use crate::task::{Task, TaskWarriorVersion};
+
+/// Read the task which is about to be added from stdin, as passed to an `on-add` hook
+pub fn read_added_task<T: TaskWarriorVersion>() -> Result<Task<T>, Error> {
+    read_added_task_from(stdin().lock())
+}
+
+/// Read the task which is about to be added from the given reader
+pub fn read_added_task_from<T: TaskWarriorVersion, R: BufRead>(mut r: R) -> Result<Task<T>, Error> {
+    let mut line = String::new();
+    r.read_line(&mut line).map_err(Error::ReaderError)?;
+    import_task(&line)
+}
+
+/// Read the original and the modified task from stdin, as passed to an `on-modify` hook
+pub fn read_modified_task<T: TaskWarriorVersion>() -> Result<(Task<T>, Task<T>), Error> {
+    read_modified_task_from(stdin().lock())
+}
+
+/// Read the original and the modified task from the given reader
+///
+/// Exactly two lines are expected, everything else is an `Error::UnexpectedHookInput`.
+pub fn read_modified_task_from<T: TaskWarriorVersion, R: BufRead>(
+    r: R,
+) -> Result<(Task<T>, Task<T>), Error> {
+    let mut lines = r.lines();
+    let original = lines
+        .next()
+        .ok_or(Error::UnexpectedHookInput("original task missing"))?
+        .map_err(Error::ReaderError)?;
+    let modified = lines
+        .next()
+        .ok_or(Error::UnexpectedHookInput("modified task missing"))?
+        .map_err(Error::ReaderError)?;
+    for line in lines {
+        if !line.map_err(Error::ReaderError)?.trim().is_empty() {
+            return Err(Error::UnexpectedHookInput("more than two tasks"));
+        }
+    }
+    Ok((import_task(&original)?, import_task(&modified)?))
+}
+
+/// Write the task to stdout as a single JSON line, as expected from a hook
+pub fn emit_task<T: TaskWarriorVersion>(task: &Task<T>) -> Result<(), Error> {
+    emit_task_to(task, stdout().lock())
+}
+
+/// Write the task to the given writer as a single JSON line
+pub fn emit_task_to<T: TaskWarriorVersion, W: Write>(
+    task: &Task<T>,
+    mut w: W,
+) -> Result<(), Error> {
+    serde_json::to_writer(&mut w, task).map_err(Error::from_serialize)?;
+    w.write_all(b"\n")?;
+    w.flush().map_err(Error::from)
+}
+
+/// Write a feedback message line to stderr
+pub fn emit_feedback(message: &str) -> Result<(), Error> {
+    emit_feedback_to(message, stderr().lock())
+}
+
+/// Write a feedback message line to the given writer
+pub fn emit_feedback_to<W: Write>(message: &str, mut w: W) -> Result<(), Error> {
+    writeln!(w, "{}", message)?;
+    w.flush().map_err(Error::from)
+}
+
+/// The exit code of a hook which rejects the task
+pub const REJECT_EXIT_CODE: i32 = 1;
+
+/// Reject the task: write the message to stdout and exit with `REJECT_EXIT_CODE`
+pub fn reject(message: &str) -> ! {
+    // Taskwarrior rejects the task because of the exit code, even if the message got lost
+    let _ = reject_to(message, stdout().lock());
+    exit(REJECT_EXIT_CODE)
+}
+
+/// Write the rejection message to the given writer, returns the exit code the hook has to exit
+/// with
+pub fn reject_to<W: Write>(message: &str, w: W) -> Result<i32, Error> {
+    emit_feedback_to(message, w)?;
+    Ok(REJECT_EXIT_CODE)
+}
+
+/// The context taskwarrior passes to a hook
+///
+/// Taskwarrior calls hooks with arguments like `api:2`, `args:'task add foo'`, `command:add`,
+/// `rc:/home/user/.taskrc`, `data:/home/user/.task` and `version:2.6.2`. Values which are not
+/// given are `None`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HookContext {
+    api: Option<u32>,
+    args: Option<String>,
+    command: Option<String>,
+    rc: Option<PathBuf>,
+    data: Option<PathBuf>,
+    version: Option<String>,
+}
+
+impl HookContext {
+    /// Get the context from the arguments and the environment of the current process
+    pub fn from_env() -> HookContext {
+        HookContext::from_args_and_env(env::args().skip(1), env::vars())
+    }
+
+    /// Get the context from the given arguments, without the program name, and environment
+    ///
+    /// Arguments which are not in the `name:value` form or have an unknown name are ignored. If
+    /// the arguments do not contain the rc file or the data location, they are taken from the
+    /// `TASKRC` and `TASKDATA` environment variables.
+    pub fn from_args_and_env<A, S, E>(args: A, env: E) -> HookContext
+    where
+        A: IntoIterator<Item = S>,
+        S: AsRef<str>,
+        E: IntoIterator<Item = (String, String)>,
+    {
+        let mut context = HookContext::default();
+        for arg in args {
+            let (name, value) = match arg.as_ref().split_once(':') {
+                Some(pair) => pair,
+                None => continue,
+            };
+            match name {
+                "api" => context.api = value.parse().ok(),
+                "args" => context.args = Some(value.to_owned()),
+                "command" => context.command = Some(value.to_owned()),
+                "rc" => context.rc = Some(PathBuf::from(value)),
+                "data" => context.data = Some(PathBuf::from(value)),
+                "version" => context.version = Some(value.to_owned()),
+                _ => {}
+            }
+        }
+        for (name, value) in env {
+            match name.as_str() {
+                "TASKRC" if context.rc.is_none() => context.rc = Some(PathBuf::from(value)),
+                "TASKDATA" if context.data.is_none() => context.data = Some(PathBuf::from(value)),
+                _ => {}
+            }
+        }
+        context
+    }
+
+    /// Get the version of the hook API
+    pub fn api(&self) -> Option<u32> {
+        self.api
+    }
+
+    /// Get the command line taskwarrior was called with
+    pub fn args(&self) -> Option<&str> {
+        self.args.as_deref()
+    }
+
+    /// Get the taskwarrior command, like `add` or `modify`
+    pub fn command(&self) -> Option<&str> {
+        self.command.as_deref()
+    }
+
+    /// Get the path of the rc file
+    pub fn rc(&self) -> Option<&PathBuf> {
+        self.rc.as_ref()
+    }
+
+    /// Get the path of the task data directory
+    pub fn data(&self) -> Option<&PathBuf> {
+        self.data.as_ref()
+    }
+
+    /// Get the version of taskwarrior
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::error::Error;
+    use crate::hooks::{
+        emit_feedback_to, emit_task_to, read_added_task_from, read_modified_task_from, reject_to,
+        HookContext,
+    };
+    use crate::import::import_task;
+    use crate::project::Project;
+    use crate::task::{Task, TW26};
+
+    const TASK: &str = r#"{"description":"some description","entry":"20150619T165438Z","status":"pending","uuid":"8ca953d5-18b4-4eb9-bd56-18f2e5b752f0"}
+"#;
+
+    #[test]
+    fn test_on_add_roundtrip() {
+        let mut task: Task<TW26> = read_added_task_from(TASK.as_bytes()).unwrap();
+        assert_eq!(task.description(), "some description");
+
+        task.set_project(Project::new("hooked").ok());
+        let mut out = Vec::new();
+        emit_task_to(&task, &mut out).unwrap();
+
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.ends_with('\n'));
+        assert_eq!(out.lines().count(), 1);
+        let emitted: Task<TW26> = import_task(&out).unwrap();
+        assert_eq!(emitted, task);
+    }
+
+    #[test]
+    fn test_on_add_no_input() {
+        assert!(read_added_task_from::<TW26, _>("".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_feedback() {
+        let mut out = Vec::new();
+        emit_feedback_to("Task looks fine", &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "Task looks fine\n");
+    }
+
+    #[test]
+    fn test_on_modify() {
+        let modified = TASK.replace("some description", "modified description");
+        let input = format!("{}{}", TASK, modified);
+
+        let (original, modified): (Task<TW26>, Task<TW26>) =
+            read_modified_task_from(input.as_bytes()).unwrap();
+        assert_eq!(original.description(), "some description");
+        assert_eq!(modified.description(), "modified description");
+        assert_eq!(original.uuid(), modified.uuid());
+    }
+
+    #[test]
+    fn test_on_modify_wrong_line_count() {
+        let one = read_modified_task_from::<TW26, _>(TASK.as_bytes());
+        assert!(matches!(one, Err(Error::UnexpectedHookInput(_))));
+
+        let three = TASK.repeat(3);
+        let three = read_modified_task_from::<TW26, _>(three.as_bytes());
+        assert!(matches!(three, Err(Error::UnexpectedHookInput(_))));
+    }
+
+    #[test]
+    fn test_reject() {
+        let mut out = Vec::new();
+        let code = reject_to("Tasks need a project", &mut out).unwrap();
+        assert_ne!(code, 0);
+        assert_eq!(String::from_utf8(out).unwrap(), "Tasks need a project\n");
+    }
+
+    #[test]
+    fn test_hook_context() {
+        use std::collections::HashMap;
+        use std::path::{Path, PathBuf};
+
+        let args = [
+            "api:2",
+            "args:task add project:home foo",
+            "command:add",
+            "rc:/home/user/.taskrc",
+            "version:2.6.2",
+            "unknown",
+        ];
+        let env: HashMap<String, String> = [
+            ("TASKRC", "/ignored/taskrc"),
+            ("TASKDATA", "/home/user/.task"),
+            ("HOME", "/home/user"),
+        ]
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+
+        let context = HookContext::from_args_and_env(args, env);
+        assert_eq!(context.api(), Some(2));
+        assert_eq!(context.args(), Some("task add project:home foo"));
+        assert_eq!(context.command(), Some("add"));
+        assert_eq!(
+            context.rc().map(PathBuf::as_path),
+            Some(Path::new("/home/user/.taskrc"))
+        );
+        assert_eq!(
+            context.data().map(PathBuf::as_path),
+            Some(Path::new("/home/user/.task"))
+        );
+        assert_eq!(context.version(), Some("2.6.2"));
+
+        let empty = HookContext::from_args_and_env(Vec::<String>::new(), HashMap::new());
+        assert_eq!(empty, HookContext::default());
+    }
+}
@@ -0,0 +1,171 @@
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+
+//! Module for exporting tasks as iCalendar (RFC 5545) VTODO components
+//!
+//! This module is only available with the `ical` feature.
+
+use crate::date::Date;
+use crate::status::TaskStatus;
+use crate::task::{Task, TaskWarriorVersion};
+
+/// The date-time template for UTC date-times in iCalendar.
+pub static ICAL_DATETIME_TEMPLATE: &str = "%Y%m%dT%H%M%SZ";
+
+/// Lines longer than this (in octets) have to be folded
+const MAX_LINE_LENGTH: usize = 75;
+
+/// Render a task as VTODO component
+///
+/// The uuid becomes the UID, the description the SUMMARY and the annotations the DESCRIPTION
+/// of the VTODO. The returned string uses CRLF line endings, as required by RFC 5545.
+pub fn task_to_vtodo<V: TaskWarriorVersion>(task: &Task<V>) -> String {
+    let mut lines = vec!["BEGIN:VTODO".to_owned()];
+    lines.push(format!("UID:{}", task.uuid()));
+    lines.push(format!(
+        "DTSTAMP:{}",
+        format_date(task.modified().unwrap_or_else(|| task.entry()))
+    ));
+    lines.push(format!("CREATED:{}", format_date(task.entry())));
+    if let Some(modified) = task.modified() {
+        lines.push(format!("LAST-MODIFIED:{}", format_date(modified)));
+    }
+    lines.push(format!("SUMMARY:{}", escape(task.description())));
+    if let Some(due) = task.due() {
+        lines.push(format!("DUE:{}", format_date(due)));
+    }
+    lines.push(format!("STATUS:{}", status(task.status())));
+    if *task.status() == TaskStatus::Completed {
+        if let Some(end) = task.end() {
+            lines.push(format!("COMPLETED:{}", format_date(end)));
+        }
+    }
+    if let Some(annotations) = task.annotations() {
+        if !annotations.is_empty() {
+            let description = annotations
+                .iter()
+                .map(|a| a.description().as_str())
+                .collect::<Vec<_>>()
+                .join("\n");
+            lines.push(format!("DESCRIPTION:{}", escape(&description)));
+        }
+    }
+    lines.push("END:VTODO".to_owned());
+
+    lines.iter().map(|line| fold(line) + "\r\n").collect()
+}
+
+fn format_date(date: &Date) -> String {
+    date.format(ICAL_DATETIME_TEMPLATE).to_string()
+}
+
+fn status(status: &TaskStatus) -> &'static str {
+    match status {
+        TaskStatus::Completed => "COMPLETED",
+        TaskStatus::Deleted => "CANCELLED",
+        TaskStatus::Pending | TaskStatus::Waiting | TaskStatus::Recurring => "NEEDS-ACTION",
+    }
+}
+
+/// Escape a TEXT value
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Fold a content line, so that no line is longer than 75 octets
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > MAX_LINE_LENGTH {
+            folded.push_str("\r\n ");
+            // The leading space counts towards the length of the continuation line
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDateTime;
+
+    use crate::annotation::Annotation;
+    use crate::date::{Date, TASKWARRIOR_DATETIME_TEMPLATE};
+    use crate::ical::task_to_vtodo;
+    use crate::status::TaskStatus;
+    use crate::task::{TaskBuilder, TW26};
+
+    fn mkdate(s: &str) -> Date {
+        let n = NaiveDateTime::parse_from_str(s, TASKWARRIOR_DATETIME_TEMPLATE);
+        Date::from(n.unwrap())
+    }
+
+    #[test]
+    fn test_vtodo() {
+        let task = TaskBuilder::<TW26>::default()
+            .description("Buy milk, eggs; bread")
+            .uuid(uuid::uuid!("8ca953d5-18b4-4eb9-bd56-18f2e5b752f0"))
+            .entry(mkdate("20150619T165438Z"))
+            .due(mkdate("20150620T120000Z"))
+            .annotations(vec![
+                Annotation::new(mkdate("20150619T170000Z"), "first".to_owned()),
+                Annotation::new(mkdate("20150619T180000Z"), "second".to_owned()),
+            ])
+            .build()
+            .unwrap();
+
+        let vtodo = task_to_vtodo(&task);
+        let lines: Vec<&str> = vtodo.split("\r\n").collect();
+        assert_eq!(lines.first(), Some(&"BEGIN:VTODO"));
+        assert!(lines.contains(&"UID:8ca953d5-18b4-4eb9-bd56-18f2e5b752f0"));
+        assert!(lines.contains(&"SUMMARY:Buy milk\\, eggs\\; bread"));
+        assert!(lines.contains(&"CREATED:20150619T165438Z"));
+        assert!(lines.contains(&"DUE:20150620T120000Z"));
+        assert!(lines.contains(&"STATUS:NEEDS-ACTION"));
+        assert!(lines.contains(&"DESCRIPTION:first\\nsecond"));
+        assert!(vtodo.ends_with("END:VTODO\r\n"));
+    }
+
+    #[test]
+    fn test_vtodo_completed() {
+        let mut task = TaskBuilder::<TW26>::default()
+            .description("done")
+            .build()
+            .unwrap();
+        task.complete();
+
+        let vtodo = task_to_vtodo(&task);
+        assert!(vtodo.contains("STATUS:COMPLETED\r\n"));
+        assert!(vtodo.contains("\r\nCOMPLETED:"));
+        assert_eq!(*task.status(), TaskStatus::Completed);
+    }
+
+    #[test]
+    fn test_vtodo_folding() {
+        let task = TaskBuilder::<TW26>::default()
+            .description("x".repeat(200))
+            .build()
+            .unwrap();
+
+        let vtodo = task_to_vtodo(&task);
+        assert!(vtodo.split("\r\n").all(|line| line.len() <= 75));
+        assert!(vtodo.contains("\r\n x"));
+    }
+}
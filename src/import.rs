@@ -6,46 +6,341 @@
 
 //! Module containing the `import()` function
 
+use std::fs::File;
 use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Bytes;
 use std::io::Read;
+use std::path::Path;
 
+use serde::de::Error as SerdeError;
 use serde_json;
+use serde_json::{Map, Value};
 
 use crate::error::Error;
-use crate::task::{Task, TaskWarriorVersion};
+use crate::task::{Task, TaskWarriorVersion, STANDARD_FIELDS};
 
 /// Import taskwarrior-exported JSON. This expects an JSON Array of objects, as exported by
 /// taskwarrior.
 pub fn import<T: TaskWarriorVersion, R: Read>(r: R) -> Result<Vec<Task<T>>, Error> {
-    serde_json::from_reader(r).map_err(Error::from)
+    serde_json::from_reader(r).map_err(Error::from_deserialize)
+}
+
+/// Import taskwarrior-exported JSON from the file at the given path.
+pub fn import_from_path<T: TaskWarriorVersion, P: AsRef<Path>>(
+    p: P,
+) -> Result<Vec<Task<T>>, Error> {
+    import(BufReader::new(File::open(p)?))
+}
+
+/// Import gzip-compressed taskwarrior-exported JSON, as written by `export_gz()`.
+///
+/// This function is only available with the `gzip` feature.
+#[cfg(feature = "gzip")]
+pub fn import_gz<T: TaskWarriorVersion, R: Read>(r: R) -> Result<Vec<Task<T>>, Error> {
+    import(BufReader::new(flate2::read::GzDecoder::new(r)))
+}
+
+/// Import taskwarrior-exported JSON lazily. This expects an JSON Array of objects, as exported by
+/// taskwarrior, but only ever holds one of the objects in memory.
+///
+/// Each object is parsed on its own, so a malformed object yields an `Err` and the iterator
+/// continues with the next one. Errors in the array itself (e.g. a missing `]`) end the iteration.
+pub fn import_iter<T: TaskWarriorVersion + 'static, R: Read>(
+    r: R,
+) -> impl Iterator<Item = Result<Task<T>, Error>> {
+    ArrayElements::new(r).map(|element| {
+        element.and_then(|bytes| serde_json::from_slice(&bytes).map_err(Error::from_deserialize))
+    })
+}
+
+/// Import taskwarrior-exported JSON, keeping all tasks which could be parsed.
+///
+/// In contrast to `import()`, a malformed object does not fail the whole import. Instead, the
+/// errors for all objects which could not be parsed are collected and returned alongside the
+/// successfully parsed tasks.
+pub fn import_lenient<T: TaskWarriorVersion + 'static, R: Read>(
+    r: R,
+) -> (Vec<Task<T>>, Vec<Error>) {
+    let mut tasks = Vec::new();
+    let mut errors = Vec::new();
+    for task in import_iter(r) {
+        match task {
+            Ok(task) => tasks.push(task),
+            Err(e) => errors.push(e),
+        }
+    }
+    (tasks, errors)
+}
+
+/// Import taskwarrior-exported JSON in either of the shapes taskwarrior exports, depending on the
+/// `json.array` setting: a JSON Array of objects, or one object per line.
+///
+/// The shape is detected by whether the input starts with `[`. Stray and trailing commas between
+/// the objects as well as blank lines are tolerated in both shapes. Parse errors of the line-wise
+/// shape are reported as `Error::ParseAtLine`.
+pub fn import_auto<T: TaskWarriorVersion + 'static, R: Read>(
+    mut r: R,
+) -> Result<Vec<Task<T>>, Error> {
+    let mut s = String::new();
+    r.read_to_string(&mut s).map_err(Error::ReaderError)?;
+
+    if s.trim_start().starts_with('[') {
+        return import_iter(s.as_bytes()).collect();
+    }
+
+    s.lines()
+        .enumerate()
+        .map(|(n, line)| (n, line.trim().trim_end_matches(',')))
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(n, line)| {
+            serde_json::from_str(line).map_err(|source| Error::ParseAtLine {
+                line: n + 1,
+                source,
+            })
+        })
+        .collect()
 }
 
 /// Import a single JSON-formatted Task
+///
+/// If the task can not be parsed, the error is an `Error::ParseWithInput` with the beginning of
+/// `s`.
 pub fn import_task<T: TaskWarriorVersion>(s: &str) -> Result<Task<T>, Error> {
-    serde_json::from_str(s).map_err(Error::from)
+    serde_json::from_str(s).map_err(|e| Error::with_input(s, e))
+}
+
+/// Import a single Task from a JSON `Value`, e.g. a task object embedded in a larger document
+pub fn task_from_value<T: TaskWarriorVersion>(v: Value) -> Result<Task<T>, Error> {
+    serde_json::from_value(v).map_err(Error::from_deserialize)
+}
+
+/// Import tasks from a JSON `Value`. This expects an JSON Array of objects, like `import()`.
+pub fn tasks_from_value<T: TaskWarriorVersion>(v: Value) -> Result<Vec<Task<T>>, Error> {
+    serde_json::from_value(v).map_err(Error::from_deserialize)
+}
+
+/// Import taskwarrior-exported JSON, keeping unknown fields as they are
+///
+/// This expects an JSON Array of objects, like `import()`. In contrast to `import()`, only the
+/// fields named in `udas` become UDAs. All other fields which are not standard fields of
+/// taskwarrior, e.g. fields added by a newer taskwarrior version, are kept untyped in
+/// `Task::extra()` and are exported unchanged.
+pub fn import_with_udas<T: TaskWarriorVersion, R: Read>(
+    r: R,
+    udas: &[&str],
+) -> Result<Vec<Task<T>>, Error> {
+    let values: Vec<Value> = serde_json::from_reader(r).map_err(Error::from_deserialize)?;
+    values
+        .into_iter()
+        .map(|v| task_from_value_with_udas(v, udas))
+        .collect()
+}
+
+/// Import a single Task from a JSON `Value`, keeping unknown fields as they are
+///
+/// See `import_with_udas()`.
+pub fn task_from_value_with_udas<T: TaskWarriorVersion>(
+    v: Value,
+    udas: &[&str],
+) -> Result<Task<T>, Error> {
+    let fields = match v {
+        Value::Object(fields) => fields,
+        other => return task_from_value(other),
+    };
+    let (known, extra): (Map<String, Value>, Map<String, Value>) =
+        fields.into_iter().partition(|(name, _)| {
+            STANDARD_FIELDS.contains(&name.as_str()) || udas.contains(&name.as_str())
+        });
+
+    let mut task: Task<T> = task_from_value(Value::Object(known))?;
+    *task.extra_mut() = extra;
+    Ok(task)
 }
 
 /// Reads line by line and tries to parse a task-object per line.
+///
+/// Parse errors are reported as `Error::ParseAtLine`, carrying the 1-based number of the line.
 pub fn import_tasks<T: TaskWarriorVersion, BR: BufRead>(r: BR) -> Vec<Result<Task<T>, Error>> {
     let mut vt = Vec::new();
-    for line in r.lines() {
-        if let Err(err) = line {
-            vt.push(Err(Error::from(err)));
-            continue;
-        }
-        // Unwrap is safe because of continue above
-        if line.as_ref().unwrap().is_empty() {
+    for (n, line) in r.lines().enumerate() {
+        let line = match line {
+            Ok(line) => line,
+            Err(err) => {
+                vt.push(Err(Error::ReaderError(err)));
+                continue;
+            }
+        };
+        if line.is_empty() {
             // Empty strings are not usable, and shall be silently ignored
             continue;
         }
-        vt.push(import_task(line.unwrap().as_str()));
+        vt.push(
+            serde_json::from_str(&line).map_err(|source| Error::ParseAtLine {
+                line: n + 1,
+                source,
+            }),
+        );
     }
     vt
 }
 
+/// Collect the successfully imported tasks of e.g. `import_tasks()`, dropping the errors
+pub fn collect_ok<T, I>(results: I) -> Vec<Task<T>>
+where
+    T: TaskWarriorVersion,
+    I: IntoIterator<Item = Result<Task<T>, Error>>,
+{
+    results.into_iter().filter_map(Result::ok).collect()
+}
+
+/// Collect the imported tasks of e.g. `import_tasks()`, or return the first error
+pub fn collect_all<T, I>(results: I) -> Result<Vec<Task<T>>, Error>
+where
+    T: TaskWarriorVersion,
+    I: IntoIterator<Item = Result<Task<T>, Error>>,
+{
+    results.into_iter().collect()
+}
+
+/// Iterator over the raw bytes of the elements of a top-level JSON Array
+struct ArrayElements<R: Read> {
+    bytes: Bytes<BufReader<R>>,
+    peeked: Option<u8>,
+    started: bool,
+    done: bool,
+}
+
+impl<R: Read> ArrayElements<R> {
+    fn new(r: R) -> Self {
+        ArrayElements {
+            bytes: BufReader::new(r).bytes(),
+            peeked: None,
+            started: false,
+            done: false,
+        }
+    }
+
+    fn next_byte(&mut self) -> Result<Option<u8>, Error> {
+        match self.peeked.take() {
+            Some(b) => Ok(Some(b)),
+            None => self.bytes.next().transpose().map_err(Error::ReaderError),
+        }
+    }
+
+    fn next_non_whitespace(&mut self) -> Result<Option<u8>, Error> {
+        loop {
+            match self.next_byte()? {
+                Some(b) if b.is_ascii_whitespace() => continue,
+                other => return Ok(other),
+            }
+        }
+    }
+
+    /// Read the next element, returns `None` once the closing `]` was read
+    fn read_element(&mut self) -> Result<Option<Vec<u8>>, Error> {
+        if !self.started {
+            self.started = true;
+            match self.next_non_whitespace()? {
+                Some(b'[') => {}
+                None => return Ok(None),
+                Some(_) => {
+                    return Err(Error::ParserError(serde_json::Error::custom(
+                        "expected `[`",
+                    )))
+                }
+            }
+        }
+
+        // Separating (and stray trailing) commas between elements are skipped
+        let first = loop {
+            match self.next_non_whitespace()? {
+                Some(b',') => continue,
+                Some(b']') => return Ok(None),
+                Some(b) => break b,
+                None => {
+                    return Err(Error::ParserError(serde_json::Error::custom(
+                        "expected `]`",
+                    )))
+                }
+            }
+        };
+
+        let mut element = vec![first];
+        let mut depth: usize = usize::from(first == b'{' || first == b'[');
+        let mut in_string = first == b'"';
+        let mut escaped = false;
+
+        loop {
+            let b = match self.next_byte()? {
+                Some(b) => b,
+                // Let the JSON parser report the truncated element
+                None => return Ok(Some(element)),
+            };
+
+            if in_string {
+                element.push(b);
+                if escaped {
+                    escaped = false;
+                } else if b == b'\\' {
+                    escaped = true;
+                } else if b == b'"' {
+                    in_string = false;
+                    if depth == 0 {
+                        return Ok(Some(element));
+                    }
+                }
+                continue;
+            }
+
+            match b {
+                b',' | b']' if depth == 0 => {
+                    self.peeked = Some(b);
+                    return Ok(Some(element));
+                }
+                b'"' => in_string = true,
+                b'{' | b'[' => depth += 1,
+                b'}' | b']' if depth > 0 => {
+                    depth -= 1;
+                    if depth == 0 {
+                        element.push(b);
+                        return Ok(Some(element));
+                    }
+                }
+                _ => {}
+            }
+            element.push(b);
+        }
+    }
+}
+
+impl<R: Read> Iterator for ArrayElements<R> {
+    type Item = Result<Vec<u8>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match self.read_element() {
+            Ok(Some(element)) => Some(Ok(element)),
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use crate::import::{import, import_task, import_tasks};
+    use crate::import::{
+        collect_all, collect_ok, import, import_auto, import_iter, import_lenient, import_task,
+        import_tasks, task_from_value, tasks_from_value,
+    };
     use crate::task::{Task, TW25, TW26};
 
     #[test]
@@ -192,7 +487,7 @@ mod test {
             Uuid::parse_str("8ca953d5-18b4-4eb9-bd56-18f2e5b752f0").unwrap()
         );
         assert_eq!(task.modified(), Some(&mkdate("20160327T164007Z")));
-        assert_eq!(task.project(), Some(&String::from("someproject")));
+        assert_eq!(task.project().unwrap(), "someproject");
         if let Some(tags) = task.tags() {
             for tag in tags {
                 let any_tag = ["some", "tags", "are", "here"].iter().any(|t| tag == *t);
@@ -221,4 +516,319 @@ mod test {
         assert_eq!(*import0.status(), TaskStatus::Waiting);
         assert_eq!(*import1.status(), TaskStatus::Waiting);
     }
+
+    #[test]
+    fn test_import_iter() {
+        let s = r#"
+[
+    {
+        "description": "first",
+        "entry": "20150619T165438Z",
+        "status": "pending",
+        "uuid": "8ca953d5-18b4-4eb9-bd56-18f2e5b752f0"
+    },
+    {
+        "description": "second, with [brackets] and {braces} and \"quotes\"",
+        "entry": "20150623T181011Z",
+        "status": "waiting",
+        "uuid": "54d49ffc-a06b-4dd8-b7d1-db5f50594312",
+        "wait": "20160508T163718Z"
+    }
+]
+"#;
+        let imported: Vec<_> = import_iter::<TW26, _>(s.as_bytes()).collect();
+        assert_eq!(imported.len(), 2);
+        assert!(imported.iter().all(Result::is_ok));
+        assert_eq!(
+            imported[1].as_ref().unwrap().description(),
+            "second, with [brackets] and {braces} and \"quotes\""
+        );
+    }
+
+    #[test]
+    fn test_import_iter_malformed_object() {
+        let s = r#"
+[
+    {
+        "description": "first",
+        "entry": "20150619T165438Z",
+        "status": "pending",
+        "uuid": "8ca953d5-18b4-4eb9-bd56-18f2e5b752f0"
+    },
+    {
+        "description": "broken",
+        "entry": "not a date",
+        "status": "pending",
+        "uuid": "54d49ffc-a06b-4dd8-b7d1-db5f50594312"
+    },
+    {
+        "description": "third",
+        "entry": "20150919T222323Z",
+        "status": "pending",
+        "uuid": "08ee8dce-cb97-4c8c-9940-c9a440e90119"
+    }
+]
+"#;
+        let imported: Vec<_> = import_iter::<TW26, _>(s.as_bytes()).collect();
+        assert_eq!(imported.len(), 3);
+        assert!(imported[0].is_ok());
+        assert!(imported[1].is_err());
+        assert!(imported[2].is_ok());
+        assert_eq!(imported[2].as_ref().unwrap().description(), "third");
+    }
+
+    #[test]
+    fn test_import_iter_empty() {
+        assert_eq!(import_iter::<TW26, _>("[]".as_bytes()).count(), 0);
+        assert_eq!(import_iter::<TW26, _>("".as_bytes()).count(), 0);
+    }
+
+    #[test]
+    fn test_import_iter_truncated() {
+        let s = r#"[{"description": "first", "entry": "20150619T165438Z", "#;
+        let imported: Vec<_> = import_iter::<TW26, _>(s.as_bytes()).collect();
+        assert_eq!(imported.len(), 2);
+        assert!(imported[0].is_err());
+        assert!(imported[1].is_err());
+    }
+
+    #[test]
+    fn test_import_tasks_error_line() {
+        use crate::error::Error;
+        use std::io::BufReader;
+        let s = r#"{"description":"good","entry":"20150619T165438Z","status":"pending","uuid":"8ca953d5-18b4-4eb9-bd56-18f2e5b752f0"}
+
+{"description":"bad","entry":"20150619T165438Z","status":"pending","uuid":"#;
+        let imported = import_tasks::<TW26, _>(BufReader::new(s.as_bytes()));
+        assert_eq!(imported.len(), 2);
+        assert!(imported[0].is_ok());
+        match imported[1] {
+            Err(Error::ParseAtLine { line, .. }) => assert_eq!(line, 3),
+            ref other => panic!("Expected ParseAtLine error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_import_lenient() {
+        let s = r#"
+[
+    {
+        "description": "first",
+        "entry": "20150619T165438Z",
+        "status": "pending",
+        "uuid": "8ca953d5-18b4-4eb9-bd56-18f2e5b752f0"
+    },
+    {
+        "description": "no status and uuid",
+        "entry": "20150623T181011Z"
+    },
+    {
+        "description": "third",
+        "entry": "20150919T222323Z",
+        "status": "pending",
+        "uuid": "08ee8dce-cb97-4c8c-9940-c9a440e90119"
+    }
+]
+"#;
+        let (tasks, errors) = import_lenient::<TW26, _>(s.as_bytes());
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(tasks[0].description(), "first");
+        assert_eq!(tasks[1].description(), "third");
+    }
+
+    #[test]
+    fn test_import_parser_error() {
+        use crate::error::Error;
+        use std::error::Error as StdError;
+
+        let imported = import::<TW26, _>(r#"[{"description": 5}]"#.as_bytes());
+        match imported {
+            Err(ref e @ Error::ParserError(_)) => {
+                let source = e.source().expect("ParserError without source");
+                assert!(source.is::<serde_json::Error>());
+            }
+            other => panic!("Expected ParserError, got {:?}", other),
+        }
+
+        let imported = import_task::<TW26>("{");
+        assert!(matches!(imported, Err(Error::ParseWithInput { .. })));
+    }
+
+    #[test]
+    fn test_import_task_error_input() {
+        use crate::error::{Error, MAX_INPUT_SNIPPET};
+
+        let broken = r#"{"description": "d", "entry": oops}"#;
+        match import_task::<TW26>(broken) {
+            Err(Error::ParseWithInput { input, source }) => {
+                assert_eq!(input, broken);
+                assert!(source.is_syntax());
+            }
+            other => panic!("Expected ParseWithInput, got {:?}", other),
+        }
+
+        let long = format!(r#"{{"description": "{}""#, "x".repeat(500));
+        match import_task::<TW26>(&long) {
+            Err(Error::ParseWithInput { input, .. }) => {
+                assert!(input.ends_with("..."));
+                assert_eq!(input.chars().count(), MAX_INPUT_SNIPPET + 3);
+                assert!(long.starts_with(input.trim_end_matches("...")));
+            }
+            other => panic!("Expected ParseWithInput, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_import_reader_error() {
+        use crate::error::Error;
+        use std::io::{self, Read};
+
+        struct FailingReader;
+        impl Read for FailingReader {
+            fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+                Err(io::Error::new(io::ErrorKind::BrokenPipe, "broken"))
+            }
+        }
+
+        let imported = import::<TW26, _>(FailingReader);
+        assert!(matches!(imported, Err(Error::ReaderError(_))));
+    }
+
+    #[test]
+    fn test_import_tasks_terminates_at_eof() {
+        use std::io::BufReader;
+        let line = r#"{"description":"test","entry":"20150619T165438Z","status":"pending","uuid":"8ca953d5-18b4-4eb9-bd56-18f2e5b752f0"}"#;
+
+        for input in [
+            format!("{}\n{}", line, line),
+            format!("{}\n{}\n", line, line),
+        ] {
+            let imported = import_tasks::<TW26, _>(BufReader::new(input.as_bytes()));
+            assert_eq!(imported.len(), 2);
+            assert!(imported.iter().all(Result::is_ok));
+        }
+    }
+
+    #[test]
+    fn test_import_iter_invalid_json() {
+        let s = r#"[
+{"description":"good","entry":"20150619T165438Z","status":"pending","uuid":"8ca953d5-18b4-4eb9-bd56-18f2e5b752f0"},
+{"description": , "status" "pending"},
+{"description":"good","entry":"20150619T165438Z","status":"pending","uuid":"54d49ffc-a06b-4dd8-b7d1-db5f50594312"}
+]"#;
+        let imported: Vec<_> = import_iter::<TW26, _>(s.as_bytes()).collect();
+        assert_eq!(imported.len(), 3);
+        assert!(imported[0].is_ok());
+        assert!(imported[1].is_err());
+        assert!(imported[2].is_ok());
+    }
+
+    #[test]
+    fn test_import_auto() {
+        let one = r#"{"description":"one","entry":"20150619T165438Z","status":"pending","uuid":"8ca953d5-18b4-4eb9-bd56-18f2e5b752f0"}"#;
+        let two = r#"{"description":"two","entry":"20150619T165438Z","status":"pending","uuid":"54d49ffc-a06b-4dd8-b7d1-db5f50594312"}"#;
+
+        let array = format!("[\n{},\n{},\n]\n", one, two);
+        let lines = format!("{},\n\n{}\n", one, two);
+
+        let from_array = import_auto::<TW26, _>(array.as_bytes()).unwrap();
+        let from_lines = import_auto::<TW26, _>(lines.as_bytes()).unwrap();
+        assert_eq!(from_array.len(), 2);
+        assert_eq!(from_array, from_lines);
+        assert_eq!(
+            from_array,
+            import::<TW26, _>(format!("[{},{}]", one, two).as_bytes()).unwrap()
+        );
+
+        assert!(import_auto::<TW26, _>("".as_bytes()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_import_auto_line_error() {
+        use crate::error::Error;
+
+        let s = "\n{\"description\": }\n";
+        match import_auto::<TW26, _>(s.as_bytes()) {
+            Err(Error::ParseAtLine { line, .. }) => assert_eq!(line, 2),
+            other => panic!("Expected ParseAtLine, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_task_from_value() {
+        let wrapper: serde_json::Value = serde_json::from_str(
+            r#"{
+                "kind": "backup",
+                "task": {
+                    "description": "test",
+                    "entry": "20150619T165438Z",
+                    "status": "pending",
+                    "uuid": "8ca953d5-18b4-4eb9-bd56-18f2e5b752f0",
+                    "estimate": 30
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let task: Task<TW26> = task_from_value(wrapper["task"].clone()).unwrap();
+        assert_eq!(task.description(), "test");
+        assert_eq!(task.uda().len(), 1);
+
+        let tasks: Vec<Task<TW26>> =
+            tasks_from_value(serde_json::Value::Array(vec![wrapper["task"].clone()])).unwrap();
+        assert_eq!(tasks, [task]);
+
+        assert!(task_from_value::<TW26>(wrapper).is_err());
+    }
+
+    #[test]
+    fn test_collect_import_tasks() {
+        use crate::error::Error;
+
+        let s = r#"{"description":"one","entry":"20150619T165438Z","status":"pending","uuid":"8ca953d5-18b4-4eb9-bd56-18f2e5b752f0"}
+{"description": }
+{"description":"two","entry":"20150619T165438Z","status":"pending","uuid":"54d49ffc-a06b-4dd8-b7d1-db5f50594312"}
+"#;
+
+        let ok: Vec<Task<TW26>> = collect_ok(import_tasks(s.as_bytes()));
+        let descriptions: Vec<&str> = ok.iter().map(|t| t.description().as_str()).collect();
+        assert_eq!(descriptions, ["one", "two"]);
+
+        match collect_all::<TW26, _>(import_tasks(s.as_bytes())) {
+            Err(Error::ParseAtLine { line, .. }) => assert_eq!(line, 2),
+            other => panic!("Expected ParseAtLine, got {:?}", other),
+        }
+
+        let only_ok = ok.clone().into_iter().map(Ok);
+        assert_eq!(collect_all(only_ok).unwrap(), ok);
+    }
+
+    #[test]
+    fn test_import_with_udas() {
+        use crate::export::export;
+        use crate::import::import_with_udas;
+        use crate::uda::UDAValue;
+
+        let s = r#"[{"description":"test","entry":"20150619T165438Z","status":"pending","uuid":"8ca953d5-18b4-4eb9-bd56-18f2e5b752f0","estimate":"30min","future":{"kind":"new","values":[1,true,null]},"futurestring":"1"}]"#;
+
+        let tasks: Vec<Task<TW26>> = import_with_udas(s.as_bytes(), &["estimate"]).unwrap();
+        let task = &tasks[0];
+        assert_eq!(task.uda().len(), 1);
+        assert_eq!(task.uda()["estimate"], UDAValue::Str("30min".to_owned()));
+        assert_eq!(
+            task.extra()["future"],
+            serde_json::json!({"kind": "new", "values": [1, true, null]})
+        );
+        assert_eq!(task.extra()["futurestring"], "1");
+
+        let mut buf = Vec::new();
+        export(&tasks, &mut buf).unwrap();
+        let exported: serde_json::Value = serde_json::from_slice(&buf).unwrap();
+        let original: serde_json::Value = serde_json::from_str(s).unwrap();
+        assert_eq!(exported, original);
+
+        // Plain import can not represent the unknown field
+        assert!(import::<TW26, _>(s.as_bytes()).is_err());
+    }
 }
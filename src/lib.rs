@@ -44,14 +44,56 @@
 )]
 
 pub mod annotation;
+pub mod clock;
+#[cfg(feature = "csv")]
+pub mod csv;
 pub mod date;
+pub mod duration;
 pub mod error;
+pub mod export;
+pub mod field;
+pub mod filter;
+pub mod hooks;
+#[cfg(feature = "ical")]
+pub mod ical;
 pub mod import;
+pub mod markdown;
+pub mod mask;
+pub mod org;
+pub mod prelude;
 pub mod priority;
 pub mod project;
+pub mod recurrence;
+#[cfg(feature = "schema")]
+pub mod schema;
 pub mod status;
+pub mod store;
 pub mod tag;
 pub mod task;
+pub mod todotxt;
+#[cfg(feature = "binary")]
 pub mod tw;
+#[cfg(feature = "tokio")]
+pub mod tw_async;
 pub mod uda;
 pub mod urgency;
+
+#[cfg(all(test, not(feature = "binary")))]
+mod test {
+    // Only built with `--no-default-features`, to make sure the serde types work without the
+    // `binary` feature
+    use crate::export::export;
+    use crate::import::import;
+    use crate::task::Task;
+
+    #[test]
+    fn test_without_binary_feature() {
+        let s = r#"[{"description":"test","entry":"20150619T165438Z","status":"pending","uuid":"8ca953d5-18b4-4eb9-bd56-18f2e5b752f0"}]"#;
+        let tasks: Vec<Task> = import(s.as_bytes()).unwrap();
+
+        let mut buf = Vec::new();
+        export(&tasks, &mut buf).unwrap();
+        let imported: Vec<Task> = import(&buf[..]).unwrap();
+        assert_eq!(tasks, imported);
+    }
+}
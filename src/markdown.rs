@@ -0,0 +1,91 @@
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+
+//! Module for rendering tasks as Markdown checklist
+
+use crate::status::TaskStatus;
+use crate::task::{Task, TaskWarriorVersion};
+
+/// Render tasks as GitHub-style task list, one item per line
+///
+/// Completed tasks are checked, deleted tasks are left out. An item looks like
+///
+/// ```text
+/// - [ ] **project** description `+tag` (due 2024-01-10)
+/// ```
+pub fn render_markdown<V: TaskWarriorVersion>(tasks: &[Task<V>]) -> String {
+    tasks
+        .iter()
+        .filter(|t| *t.status() != TaskStatus::Deleted)
+        .map(|t| render_item(t) + "\n")
+        .collect()
+}
+
+fn render_item<V: TaskWarriorVersion>(task: &Task<V>) -> String {
+    let checkbox = if *task.status() == TaskStatus::Completed {
+        "[x]"
+    } else {
+        "[ ]"
+    };
+    let mut item = format!("- {} ", checkbox);
+    if let Some(project) = task.project() {
+        item.push_str(&format!("**{}** ", project));
+    }
+    item.push_str(task.description());
+    for tag in task.tags().into_iter().flatten() {
+        item.push_str(&format!(" `+{}`", tag));
+    }
+    if let Some(due) = task.due() {
+        item.push_str(&format!(" (due {})", due.format("%Y-%m-%d")));
+    }
+    item
+}
+
+#[cfg(test)]
+mod test {
+    use crate::import::import;
+    use crate::markdown::render_markdown;
+    use crate::task::TW26;
+
+    #[test]
+    fn test_render_markdown() {
+        let s = r#"
+[
+    {
+        "description": "pending task",
+        "entry": "20150619T165438Z",
+        "project": "someproject",
+        "status": "pending",
+        "tags": ["some", "tags"],
+        "uuid": "8ca953d5-18b4-4eb9-bd56-18f2e5b752f0",
+        "due": "20160508T164007Z"
+    },
+    {
+        "description": "completed task",
+        "entry": "20150623T181011Z",
+        "end": "20150624T181011Z",
+        "status": "completed",
+        "uuid": "54d49ffc-a06b-4dd8-b7d1-db5f50594312"
+    },
+    {
+        "description": "deleted task",
+        "entry": "20150623T181011Z",
+        "end": "20150624T181011Z",
+        "status": "deleted",
+        "uuid": "08ee8dce-cb97-4c8c-9940-c9a440e90119"
+    }
+]
+"#;
+        let tasks = import::<TW26, _>(s.as_bytes()).unwrap();
+        let markdown = render_markdown(&tasks);
+
+        assert_eq!(
+            markdown,
+            "- [ ] **someproject** pending task `+some` `+tags` (due 2016-05-08)\n\
+             - [x] completed task\n"
+        );
+    }
+}
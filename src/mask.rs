@@ -0,0 +1,167 @@
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+
+//! Module containing the `Mask` type, which taskwarrior uses internally for recurrence
+
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+
+/// The state of a single child of a recurring task, as stored in the `Mask`
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum MaskState {
+    /// The child is pending, written as `-`
+    Pending,
+    /// The child is completed, written as `+`
+    Completed,
+    /// The child is deleted, written as `X`
+    Deleted,
+    /// The child is waiting, written as `W`
+    Waiting,
+}
+
+impl MaskState {
+    /// Get the character taskwarrior uses for this state
+    pub fn as_char(self) -> char {
+        match self {
+            MaskState::Pending => '-',
+            MaskState::Completed => '+',
+            MaskState::Deleted => 'X',
+            MaskState::Waiting => 'W',
+        }
+    }
+
+    /// Get the state for a character of a mask, if it is one
+    pub fn from_char(c: char) -> Option<MaskState> {
+        match c {
+            '-' => Some(MaskState::Pending),
+            '+' => Some(MaskState::Completed),
+            'X' => Some(MaskState::Deleted),
+            'W' => Some(MaskState::Waiting),
+            _ => None,
+        }
+    }
+}
+
+/// The mask of a recurring task
+///
+/// The mask holds the state of every child taskwarrior has created for a recurring task, where the
+/// `imask` of a child is its index in the mask of the parent.
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Mask(Vec<MaskState>);
+
+impl Mask {
+    /// Create an empty mask
+    pub fn new() -> Mask {
+        Mask::default()
+    }
+
+    /// Get the states in the mask
+    pub fn states(&self) -> &[MaskState] {
+        &self.0
+    }
+
+    /// Get the state of the child with the given `imask`
+    pub fn get(&self, imask: u64) -> Option<MaskState> {
+        usize::try_from(imask)
+            .ok()
+            .and_then(|i| self.0.get(i))
+            .copied()
+    }
+
+    /// Append the state of the next child
+    pub fn push(&mut self, state: MaskState) {
+        self.0.push(state)
+    }
+
+    /// Get the number of children in the mask
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Check whether the mask is empty
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl From<Vec<MaskState>> for Mask {
+    fn from(states: Vec<MaskState>) -> Mask {
+        Mask(states)
+    }
+}
+
+impl fmt::Display for Mask {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0
+            .iter()
+            .try_for_each(|state| fmt::Write::write_char(f, state.as_char()))
+    }
+}
+
+impl FromStr for Mask {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Mask, Error> {
+        s.chars()
+            .map(MaskState::from_char)
+            .collect::<Option<Vec<_>>>()
+            .map(Mask)
+            .ok_or_else(|| Error::InvalidMask(s.to_owned()))
+    }
+}
+
+impl TryFrom<String> for Mask {
+    type Error = Error;
+
+    fn try_from(s: String) -> Result<Mask, Error> {
+        s.parse()
+    }
+}
+
+impl From<Mask> for String {
+    fn from(mask: Mask) -> String {
+        mask.to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::error::Error;
+    use crate::mask::{Mask, MaskState};
+
+    #[test]
+    fn test_mask_roundtrip() {
+        let mask: Mask = "+-XW".parse().unwrap();
+        assert_eq!(
+            mask.states(),
+            &[
+                MaskState::Completed,
+                MaskState::Pending,
+                MaskState::Deleted,
+                MaskState::Waiting
+            ]
+        );
+        assert_eq!(mask.get(2), Some(MaskState::Deleted));
+        assert_eq!(mask.get(4), None);
+        assert_eq!(mask.to_string(), "+-XW");
+
+        assert_eq!(serde_json::to_string(&mask).unwrap(), r#""+-XW""#);
+        assert_eq!(serde_json::from_str::<Mask>(r#""+-XW""#).unwrap(), mask);
+    }
+
+    #[test]
+    fn test_mask_invalid() {
+        assert!(matches!("+-?".parse::<Mask>(), Err(Error::InvalidMask(_))));
+        assert!(serde_json::from_str::<Mask>(r#""abc""#).is_err());
+        assert!("".parse::<Mask>().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,101 @@
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+
+//! Module for exporting tasks as Emacs org-mode headings
+
+use crate::project::Project;
+use crate::status::TaskStatus;
+use crate::tag::Tag;
+use crate::task::{Task, TaskWarriorVersion};
+
+/// The template for org-mode timestamps, without the surrounding brackets
+pub static ORG_DATE_TEMPLATE: &str = "%Y-%m-%d %a";
+
+/// Render a task as org-mode heading
+///
+/// Completed and deleted tasks are `DONE`, all others `TODO`. The project and the tags become org
+/// tags on the heading line, the due date a `DEADLINE` and the annotations a list below the
+/// heading. The returned string ends with a newline.
+pub fn to_org<V: TaskWarriorVersion>(task: &Task<V>) -> String {
+    let keyword = match task.status() {
+        TaskStatus::Completed | TaskStatus::Deleted => "DONE",
+        TaskStatus::Pending | TaskStatus::Waiting | TaskStatus::Recurring => "TODO",
+    };
+    let mut org = format!("* {} {}", keyword, task.description());
+
+    let tags: Vec<String> = task
+        .project()
+        .map(Project::as_str)
+        .into_iter()
+        .chain(task.tags().into_iter().flatten().map(Tag::as_str))
+        .map(org_tag)
+        .collect();
+    if !tags.is_empty() {
+        org.push_str(&format!(" :{}:", tags.join(":")));
+    }
+    org.push('\n');
+
+    if let Some(due) = task.due() {
+        org.push_str(&format!(
+            "  DEADLINE: <{}>\n",
+            due.format(ORG_DATE_TEMPLATE)
+        ));
+    }
+    for annotation in task.annotations().into_iter().flatten() {
+        org.push_str(&format!(
+            "  - [{}] {}\n",
+            annotation.entry().format(ORG_DATE_TEMPLATE),
+            annotation.description()
+        ));
+    }
+
+    org
+}
+
+/// Org tags may only contain alphanumeric characters, `_`, `@`, `#` and `%`
+fn org_tag(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            c if c.is_alphanumeric() => c,
+            '_' | '@' | '#' | '%' => c,
+            _ => '_',
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::import::import_task;
+    use crate::org::to_org;
+    use crate::task::{Task, TW26};
+
+    #[test]
+    fn test_to_org() {
+        let s = r#"{
+            "description": "some description",
+            "entry": "20150619T165438Z",
+            "project": "some.project",
+            "status": "pending",
+            "tags": ["some", "tags"],
+            "uuid": "8ca953d5-18b4-4eb9-bd56-18f2e5b752f0",
+            "due": "20160508T164007Z",
+            "annotations": [
+                {
+                    "entry": "20150623T181018Z",
+                    "description": "fooooooobar"
+                }
+            ]
+        }"#;
+        let task: Task<TW26> = import_task(s).unwrap();
+
+        assert_eq!(
+            to_org(&task),
+            "* TODO some description :some_project:some:tags:\n\
+             \x20 DEADLINE: <2016-05-08 Sun>\n\
+             \x20 - [2015-06-23 Tue] fooooooobar\n"
+        );
+    }
+}
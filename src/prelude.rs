@@ -0,0 +1,33 @@
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+
+//! Module re-exporting the commonly used types and functions
+//!
+//! ```
+//! use task_hookrs::prelude::*;
+//!
+//! let s = r#"[{"description":"test","entry":"20150619T165438Z","status":"pending","uuid":"8ca953d5-18b4-4eb9-bd56-18f2e5b752f0"}]"#;
+//! let tasks: Vec<Task<TW26>> = import(s.as_bytes()).unwrap();
+//! assert_eq!(*tasks[0].status(), TaskStatus::Pending);
+//!
+//! let mut buf = Vec::new();
+//! export(&tasks, &mut buf).unwrap();
+//! ```
+
+pub use crate::annotation::Annotation;
+pub use crate::date::Date;
+pub use crate::duration::TwDuration;
+pub use crate::error::Error;
+pub use crate::export::{export, export_task, export_tasks};
+pub use crate::import::{import, import_task, import_tasks};
+pub use crate::priority::TaskPriority;
+pub use crate::project::Project;
+pub use crate::status::TaskStatus;
+pub use crate::store::TaskStore;
+pub use crate::tag::Tag;
+pub use crate::task::{Task, TaskBuilder, TaskWarriorVersion, TW25, TW26};
+pub use crate::uda::{UDAName, UDAValue, UDA};
+pub use crate::urgency::Urgency;
@@ -6,5 +6,213 @@
 
 //! Module containing `Project` type
 
-/// Typedef for Project type.
-pub type Project = String;
+use std::borrow::Borrow;
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops::Deref;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::task::{Task, TaskWarriorVersion};
+
+/// The project of a task
+///
+/// Projects are validated on construction: an empty project means "no project" to taskwarrior,
+/// so empty projects and projects consisting of whitespace only are rejected. Subprojects are
+/// separated by dots, like `home.garden`.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Project(String);
+
+impl Project {
+    /// Create a new project, failing with `Error::InvalidProject` if it is empty
+    pub fn new<S: Into<String>>(s: S) -> Result<Project, Error> {
+        let s = s.into();
+        if s.trim().is_empty() {
+            Err(Error::InvalidProject(s))
+        } else {
+            Ok(Project(s))
+        }
+    }
+
+    /// Get the project as string slice
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Check whether this project is `ancestor` or one of its subprojects
+    ///
+    /// `work.meetings` is within `work`, but `workshop` is not.
+    pub fn is_within(&self, ancestor: &str) -> bool {
+        match self.0.strip_prefix(ancestor) {
+            Some(rest) => rest.is_empty() || rest.starts_with('.'),
+            None => false,
+        }
+    }
+}
+
+/// Move all tasks of the project `from` and its subprojects to the project `to`
+///
+/// The prefix `from` of the projects is replaced with `to`, so with `from` being `work` and `to`
+/// being `job`, a task in `work.meetings` is moved to `job.meetings`.
+pub fn rewrite_project<V: TaskWarriorVersion>(tasks: &mut [Task<V>], from: &Project, to: &Project) {
+    for task in tasks {
+        let rewritten = match task.project() {
+            Some(project) if project.is_within(from) => {
+                Project(format!("{}{}", to, &project[from.len()..]))
+            }
+            _ => continue,
+        };
+        task.set_project(Some(rewritten));
+    }
+}
+
+impl Deref for Project {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for Project {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for Project {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Project {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for Project {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Project, Error> {
+        Project::new(s)
+    }
+}
+
+impl TryFrom<String> for Project {
+    type Error = Error;
+
+    fn try_from(s: String) -> Result<Project, Error> {
+        Project::new(s)
+    }
+}
+
+impl TryFrom<&str> for Project {
+    type Error = Error;
+
+    fn try_from(s: &str) -> Result<Project, Error> {
+        Project::new(s)
+    }
+}
+
+impl From<Project> for String {
+    fn from(project: Project) -> String {
+        project.0
+    }
+}
+
+impl PartialEq<str> for Project {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for Project {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<String> for Project {
+    fn eq(&self, other: &String) -> bool {
+        self.0 == *other
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::error::Error;
+    use crate::project::{rewrite_project, Project};
+    use crate::task::Task;
+
+    #[test]
+    fn test_valid_project() {
+        let project: Project = "home.garden".parse().unwrap();
+        assert_eq!(project, "home.garden");
+        assert_eq!(project.to_string(), "home.garden");
+    }
+
+    #[test]
+    fn test_invalid_project() {
+        for s in ["", "  \t"] {
+            match s.parse::<Project>() {
+                Err(Error::InvalidProject(p)) => assert_eq!(p, s),
+                other => panic!("Expected InvalidProject, got {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_is_within() {
+        let work = Project::new("work").unwrap();
+        assert!(work.is_within(&work));
+        assert!(Project::new("work.sub").unwrap().is_within(&work));
+        assert!(!Project::new("workshop").unwrap().is_within(&work));
+        assert!(!work.is_within(&Project::new("work.sub").unwrap()));
+    }
+
+    #[test]
+    fn test_rewrite_project() {
+        let mut tasks: Vec<Task> = ["work", "work.sub", "workshop", "home"]
+            .iter()
+            .map(|p| {
+                let mut task = Task::builder().description("d").build().unwrap();
+                task.set_project(Project::new(*p).ok());
+                task
+            })
+            .collect();
+        tasks.push(Task::builder().description("d").build().unwrap());
+
+        rewrite_project(
+            &mut tasks,
+            &Project::new("work").unwrap(),
+            &Project::new("job").unwrap(),
+        );
+        let projects: Vec<Option<&str>> = tasks.iter().map(|t| t.project_str()).collect();
+        assert_eq!(
+            projects,
+            [
+                Some("job"),
+                Some("job.sub"),
+                Some("workshop"),
+                Some("home"),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn test_project_serde() {
+        let project = Project::new("home").unwrap();
+        assert_eq!(serde_json::to_string(&project).unwrap(), r#""home""#);
+        assert_eq!(
+            serde_json::from_str::<Project>(r#""home""#).unwrap(),
+            project
+        );
+        assert!(serde_json::from_str::<Project>(r#""""#).is_err());
+    }
+}
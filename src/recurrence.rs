@@ -0,0 +1,172 @@
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+
+//! Module containing the `Recurrence` type
+
+use std::str::FromStr;
+
+use chrono::{Datelike, Duration, Months, Weekday};
+
+use crate::date::Date;
+use crate::duration::{self, Parts};
+use crate::error::Error;
+
+/// The parsed form of the `recur` field of a task
+///
+/// Months and years have no fixed length, so they are kept apart from durations of fixed length.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Recurrence {
+    /// Every day from monday to friday
+    Weekdays,
+
+    /// Every n months, years are expressed as multiples of twelve months
+    Months(u32),
+
+    /// A fixed duration, like days or weeks
+    Duration(Duration),
+}
+
+impl Recurrence {
+    /// Get the `n`th occurrence of this recurrence, where the `0`th occurrence is `start`
+    ///
+    /// Each occurrence is computed from `start`, so monthly recurrences starting on the 31st
+    /// stay at the end of the month. Returns `None` if the date would be out of range.
+    pub fn occurrence(&self, start: &Date, n: u32) -> Option<Date> {
+        let date = match self {
+            Recurrence::Weekdays => {
+                let mut date = **start;
+                for _ in 0..n {
+                    date = date.checked_add_signed(Duration::days(1))?;
+                    while matches!(date.weekday(), Weekday::Sat | Weekday::Sun) {
+                        date = date.checked_add_signed(Duration::days(1))?;
+                    }
+                }
+                date
+            }
+            Recurrence::Months(m) => start.checked_add_months(Months::new(m.checked_mul(n)?))?,
+            Recurrence::Duration(d) => {
+                start.checked_add_signed(d.checked_mul(i32::try_from(n).ok()?)?)?
+            }
+        };
+        Some(Date::from(date))
+    }
+}
+
+impl FromStr for Recurrence {
+    type Err = Error;
+
+    /// Parse a taskwarrior duration
+    ///
+    /// Named forms like `daily`, `weekly`, `weekdays` or `yearly`, numeric forms like `3d` or
+    /// `2weeks` and ISO-8601 forms like `P1M` or `PT12H` are supported. Durations which mix months
+    /// with days or times are neither a number of months nor a fixed duration and thus rejected,
+    /// as are empty durations.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim().eq_ignore_ascii_case("weekdays") {
+            return Ok(Recurrence::Weekdays);
+        }
+        match duration::parse(s) {
+            Some(Parts { months: 0, fixed }) if fixed > Duration::zero() => {
+                Ok(Recurrence::Duration(fixed))
+            }
+            Some(Parts { months, fixed }) if months > 0 && fixed.is_zero() => {
+                Ok(Recurrence::Months(months))
+            }
+            _ => Err(Error::InvalidRecurrence(s.to_owned())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::Duration;
+
+    use crate::error::Error;
+    use crate::recurrence::Recurrence;
+
+    fn parse(s: &str) -> Recurrence {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_occurrence() {
+        use chrono::NaiveDateTime;
+
+        use crate::date::{Date, TASKWARRIOR_DATETIME_TEMPLATE};
+
+        let mkdate = |s| {
+            Date::from(NaiveDateTime::parse_from_str(s, TASKWARRIOR_DATETIME_TEMPLATE).unwrap())
+        };
+        let start = mkdate("20240131T120000Z");
+        let friday = mkdate("20240202T120000Z");
+
+        assert_eq!(
+            parse("monthly").occurrence(&start, 1),
+            Some(mkdate("20240229T120000Z"))
+        );
+        assert_eq!(
+            parse("monthly").occurrence(&start, 2),
+            Some(mkdate("20240331T120000Z"))
+        );
+        assert_eq!(
+            parse("weekdays").occurrence(&friday, 1),
+            Some(mkdate("20240205T120000Z"))
+        );
+        assert_eq!(
+            parse("2d").occurrence(&start, 3),
+            Some(mkdate("20240206T120000Z"))
+        );
+        assert_eq!(parse("2d").occurrence(&start, 0), Some(start));
+    }
+
+    #[test]
+    fn test_named() {
+        assert_eq!(parse("daily"), Recurrence::Duration(Duration::days(1)));
+        assert_eq!(parse("weekly"), Recurrence::Duration(Duration::weeks(1)));
+        assert_eq!(parse("monthly"), Recurrence::Months(1));
+        assert_eq!(parse("yearly"), Recurrence::Months(12));
+        assert_eq!(parse("weekdays"), Recurrence::Weekdays);
+    }
+
+    #[test]
+    fn test_numeric() {
+        assert_eq!(parse("2w"), Recurrence::Duration(Duration::weeks(2)));
+        assert_eq!(parse("3d"), Recurrence::Duration(Duration::days(3)));
+        assert_eq!(parse("3days"), Recurrence::Duration(Duration::days(3)));
+        assert_eq!(parse("6mo"), Recurrence::Months(6));
+        assert_eq!(parse("2y"), Recurrence::Months(24));
+        assert_eq!(parse("week"), Recurrence::Duration(Duration::weeks(1)));
+    }
+
+    #[test]
+    fn test_iso8601() {
+        assert_eq!(parse("P1M"), Recurrence::Months(1));
+        assert_eq!(parse("P1Y6M"), Recurrence::Months(18));
+        assert_eq!(parse("P2W"), Recurrence::Duration(Duration::weeks(2)));
+        assert_eq!(parse("P1DT12H"), Recurrence::Duration(Duration::hours(36)));
+    }
+
+    #[test]
+    fn test_invalid() {
+        for s in [
+            "",
+            "fortnightly",
+            "0d",
+            "2",
+            "3x",
+            "P",
+            "P1",
+            "P1M2D",
+            "PT1Y",
+        ] {
+            let r = s.parse::<Recurrence>();
+            assert!(
+                matches!(r, Err(Error::InvalidRecurrence(ref r)) if r == s),
+                "{s:?} parsed as {r:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,203 @@
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+
+//! Module for generating a JSON schema of the task format
+//!
+//! This module is only available with the `schema` feature.
+
+use schemars::gen::SchemaGenerator;
+use schemars::schema::{
+    InstanceType, Metadata, RootSchema, Schema, SchemaObject, StringValidation,
+};
+use schemars::{schema_for, JsonSchema};
+use uuid::Uuid;
+
+use crate::annotation::Annotation;
+use crate::date::Date;
+use crate::mask::Mask;
+use crate::priority::TaskPriority;
+use crate::project::Project;
+use crate::status::TaskStatus;
+use crate::tag::Tag;
+use crate::task::{Task, TaskWarriorVersion};
+use crate::uda::UDAValue;
+use crate::urgency::Urgency;
+
+/// The regular expression dates in the taskwarrior format match
+pub const DATE_PATTERN: &str = r"^\d{8}T\d{6}Z$";
+
+/// Get the JSON schema of a task, in the format of taskwarrior 2.6.0 and newer
+///
+/// Fields which are not standard fields are allowed as UDAs with string or number values.
+pub fn task_schema() -> RootSchema {
+    schema_for!(Task)
+}
+
+// `Task` can not derive `JsonSchema`, because the derive does not support the generic
+// `serialize_with` of `depends`, so the schema is derived from this copy of the fields instead.
+// The UDAs are added as additional properties by hand.
+#[derive(JsonSchema)]
+#[schemars(
+    rename = "Task",
+    description = "A task in the JSON format of taskwarrior"
+)]
+#[allow(dead_code)]
+struct TaskFields {
+    id: Option<u64>,
+    status: TaskStatus,
+    uuid: Uuid,
+    entry: Date,
+    description: String,
+    annotations: Option<Vec<Annotation>>,
+    depends: Option<Vec<Uuid>>,
+    due: Option<Date>,
+    end: Option<Date>,
+    imask: Option<u64>,
+    mask: Option<Mask>,
+    modified: Option<Date>,
+    parent: Option<Uuid>,
+    priority: Option<TaskPriority>,
+    project: Option<Project>,
+    recur: Option<String>,
+    scheduled: Option<Date>,
+    start: Option<Date>,
+    tags: Option<Vec<Tag>>,
+    until: Option<Date>,
+    wait: Option<Date>,
+    urgency: Option<Urgency>,
+}
+
+impl<V: TaskWarriorVersion> JsonSchema for Task<V> {
+    fn schema_name() -> String {
+        TaskFields::schema_name()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        let mut schema = TaskFields::json_schema(gen).into_object();
+        schema.object().additional_properties = Some(Box::new(UDAValue::json_schema(gen)));
+        schema.into()
+    }
+}
+
+fn string_schema(description: &str, pattern: Option<&str>) -> Schema {
+    SchemaObject {
+        instance_type: Some(InstanceType::String.into()),
+        metadata: Some(Box::new(Metadata {
+            description: Some(description.to_owned()),
+            ..Default::default()
+        })),
+        string: Some(Box::new(StringValidation {
+            pattern: pattern.map(ToOwned::to_owned),
+            ..Default::default()
+        })),
+        ..Default::default()
+    }
+    .into()
+}
+
+impl JsonSchema for Date {
+    fn schema_name() -> String {
+        "Date".to_owned()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        string_schema(
+            "A UTC date in the format YYYYMMDDTHHMMSSZ",
+            Some(DATE_PATTERN),
+        )
+    }
+}
+
+impl JsonSchema for Mask {
+    fn schema_name() -> String {
+        "Mask".to_owned()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        string_schema(
+            "The states of the children of a recurring task",
+            Some("^[-+XW]*$"),
+        )
+    }
+}
+
+impl JsonSchema for Project {
+    fn schema_name() -> String {
+        "Project".to_owned()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        string_schema("A project, subprojects are separated by dots", Some(r"\S"))
+    }
+}
+
+impl JsonSchema for Tag {
+    fn schema_name() -> String {
+        "Tag".to_owned()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        string_schema(
+            "A tag without whitespace, not starting with + or -",
+            Some(r"^[^+\-\s][^\s]*$"),
+        )
+    }
+}
+
+impl JsonSchema for Urgency {
+    fn schema_name() -> String {
+        "Urgency".to_owned()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        f64::json_schema(gen)
+    }
+}
+
+impl JsonSchema for UDAValue {
+    fn schema_name() -> String {
+        "UDAValue".to_owned()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        SchemaObject {
+            instance_type: Some(vec![InstanceType::String, InstanceType::Number].into()),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::Value;
+
+    use crate::schema::{task_schema, DATE_PATTERN};
+    use crate::task::STANDARD_FIELDS;
+
+    #[test]
+    fn test_task_schema() {
+        let schema = serde_json::to_value(task_schema()).unwrap();
+
+        let properties = schema["properties"].as_object().unwrap();
+        assert_eq!(properties.len(), STANDARD_FIELDS.len());
+        assert!(STANDARD_FIELDS.iter().all(|f| properties.contains_key(*f)));
+
+        let required = schema["required"].as_array().unwrap();
+        assert!(required.contains(&Value::from("description")));
+        assert!(required.contains(&Value::from("entry")));
+        assert!(!required.contains(&Value::from("due")));
+
+        assert_eq!(schema["properties"]["description"]["type"], "string");
+        assert_eq!(schema["properties"]["uuid"]["format"], "uuid");
+        assert_eq!(schema["definitions"]["Date"]["type"], "string");
+        assert_eq!(schema["definitions"]["Date"]["pattern"], DATE_PATTERN);
+        assert_eq!(
+            schema["additionalProperties"]["type"],
+            serde_json::json!(["string", "number"])
+        );
+    }
+}
@@ -9,7 +9,37 @@
 use std::fmt::{Display, Error as FmtError, Formatter};
 
 /// Enum for status taskwarrior supports.
+///
+/// New variants may be added in minor releases, so matches outside this crate need a wildcard
+/// arm:
+///
+/// ```
+/// use task_hookrs::status::TaskStatus;
+///
+/// fn is_done(status: &TaskStatus) -> bool {
+///     match status {
+///         TaskStatus::Completed | TaskStatus::Deleted => true,
+///         TaskStatus::Pending | TaskStatus::Waiting | TaskStatus::Recurring => false,
+///         _ => false,
+///     }
+/// }
+/// # assert!(is_done(&TaskStatus::Completed));
+/// ```
+///
+/// ```compile_fail
+/// use task_hookrs::status::TaskStatus;
+///
+/// fn is_done(status: &TaskStatus) -> bool {
+///     match status {
+///         TaskStatus::Completed | TaskStatus::Deleted => true,
+///         TaskStatus::Pending | TaskStatus::Waiting | TaskStatus::Recurring => false,
+///     }
+/// }
+/// ```
 #[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(description = "The status of a task"))]
+#[non_exhaustive]
 pub enum TaskStatus {
     /// Pending status type
     #[serde(rename = "pending")]
@@ -32,6 +62,16 @@ pub enum TaskStatus {
     Recurring,
 }
 
+impl TaskStatus {
+    /// Check whether the status is final, i.e. `Completed` or `Deleted`
+    ///
+    /// Tasks with such a status are done with, while `Pending`, `Waiting` and `Recurring` tasks
+    /// are still active.
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, TaskStatus::Completed | TaskStatus::Deleted)
+    }
+}
+
 impl Display for TaskStatus {
     fn fmt(&self, fmt: &mut Formatter) -> Result<(), FmtError> {
         match self {
@@ -43,3 +83,17 @@ impl Display for TaskStatus {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::status::TaskStatus;
+
+    #[test]
+    fn test_is_terminal() {
+        assert!(TaskStatus::Completed.is_terminal());
+        assert!(TaskStatus::Deleted.is_terminal());
+        assert!(!TaskStatus::Pending.is_terminal());
+        assert!(!TaskStatus::Waiting.is_terminal());
+        assert!(!TaskStatus::Recurring.is_terminal());
+    }
+}
@@ -0,0 +1,414 @@
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+
+//! Module containing the `TaskStore` type, a collection of tasks indexed by their uuid
+
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::iter::FromIterator;
+
+use uuid::Uuid;
+
+use crate::error::Error;
+use crate::task::{Task, TaskWarriorVersion, TW26};
+
+/// A collection of tasks, indexed by their uuid
+///
+/// ```
+/// use task_hookrs::store::TaskStore;
+/// # use task_hookrs::task::Task;
+/// # let tasks: Vec<Task> = Vec::new();
+///
+/// let store: TaskStore = tasks.into_iter().collect();
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskStore<V: TaskWarriorVersion + 'static = TW26> {
+    tasks: HashMap<Uuid, Task<V>>,
+}
+
+impl<V: TaskWarriorVersion> Default for TaskStore<V> {
+    fn default() -> Self {
+        TaskStore {
+            tasks: HashMap::new(),
+        }
+    }
+}
+
+impl<V: TaskWarriorVersion> TaskStore<V> {
+    /// Create an empty store
+    pub fn new() -> Self {
+        TaskStore::default()
+    }
+
+    /// Insert a task, returning the task with the same uuid which was replaced, if any
+    pub fn insert(&mut self, task: Task<V>) -> Option<Task<V>> {
+        self.tasks.insert(*task.uuid(), task)
+    }
+
+    /// Remove the task with the given uuid
+    pub fn remove(&mut self, uuid: &Uuid) -> Option<Task<V>> {
+        self.tasks.remove(uuid)
+    }
+
+    /// Get the task with the given uuid
+    pub fn get(&self, uuid: &Uuid) -> Option<&Task<V>> {
+        self.tasks.get(uuid)
+    }
+
+    /// Get the task with the given uuid mutable
+    pub fn get_mut(&mut self, uuid: &Uuid) -> Option<&mut Task<V>> {
+        self.tasks.get_mut(uuid)
+    }
+
+    /// Get the number of tasks in the store
+    pub fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    /// Check whether the store is empty
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+
+    /// Iterate over the tasks in the store, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = &Task<V>> {
+        self.tasks.values()
+    }
+
+    /// Get the tasks the given task depends on, in the order of its `depends`. Dependencies which
+    /// are not in the store are left out.
+    pub fn resolve_depends(&self, task: &Task<V>) -> Vec<&Task<V>> {
+        task.depends()
+            .into_iter()
+            .flatten()
+            .filter_map(|uuid| self.get(uuid))
+            .collect()
+    }
+
+    /// Find cycles in the dependencies of the tasks in the store
+    ///
+    /// Each cycle is returned as the uuids of the tasks involved, where every task depends on the
+    /// next one and the last one depends on the first one. Dependencies on tasks which are not in
+    /// the store are ignored.
+    pub fn dependency_cycles(&self) -> Vec<Vec<Uuid>> {
+        // Sorted, so the result does not depend on the order of the HashMap
+        let mut uuids: Vec<&Uuid> = self.tasks.keys().collect();
+        uuids.sort();
+        let edges = uuids.into_iter().map(|uuid| {
+            let depends = self.tasks[uuid].depends().map_or(&[][..], Vec::as_slice);
+            (*uuid, depends)
+        });
+        find_cycles(edges)
+    }
+}
+
+/// A dependency of a task, as returned by `Task::depends_resolved()`
+#[derive(Debug, PartialEq)]
+pub enum DependencyRef<'a, V: TaskWarriorVersion + 'static = TW26> {
+    /// The task the dependency points to
+    Resolved(&'a Task<V>),
+    /// The uuid of a dependency which is not in the store
+    Dangling(Uuid),
+}
+
+impl<V: TaskWarriorVersion> DependencyRef<'_, V> {
+    /// Get the uuid of the dependency
+    pub fn uuid(&self) -> &Uuid {
+        match self {
+            DependencyRef::Resolved(task) => task.uuid(),
+            DependencyRef::Dangling(uuid) => uuid,
+        }
+    }
+}
+
+/// A reference to a task which is compared and hashed by the uuid of the task only
+///
+/// This allows to put tasks in a `HashSet` or use them as keys of a `HashMap`, e.g. for
+/// deduplicating tasks by their uuid. `Task` itself compares all fields.
+#[derive(Debug)]
+pub struct TaskByUuid<'a, V: TaskWarriorVersion + 'static = TW26>(pub &'a Task<V>);
+
+impl<'a, V: TaskWarriorVersion> TaskByUuid<'a, V> {
+    /// Get the wrapped task
+    pub fn task(&self) -> &'a Task<V> {
+        self.0
+    }
+}
+
+impl<V: TaskWarriorVersion> Clone for TaskByUuid<'_, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<V: TaskWarriorVersion> Copy for TaskByUuid<'_, V> {}
+
+impl<V: TaskWarriorVersion> PartialEq for TaskByUuid<'_, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.uuid() == other.0.uuid()
+    }
+}
+
+impl<V: TaskWarriorVersion> Eq for TaskByUuid<'_, V> {}
+
+impl<V: TaskWarriorVersion> Hash for TaskByUuid<'_, V> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.uuid().hash(state)
+    }
+}
+
+impl<'a, V: TaskWarriorVersion> From<&'a Task<V>> for TaskByUuid<'a, V> {
+    fn from(task: &'a Task<V>) -> Self {
+        TaskByUuid(task)
+    }
+}
+
+impl<V: TaskWarriorVersion> FromIterator<Task<V>> for TaskStore<V> {
+    fn from_iter<I: IntoIterator<Item = Task<V>>>(iter: I) -> Self {
+        TaskStore {
+            tasks: iter.into_iter().map(|t| (*t.uuid(), t)).collect(),
+        }
+    }
+}
+
+impl<V: TaskWarriorVersion> Extend<Task<V>> for TaskStore<V> {
+    fn extend<I: IntoIterator<Item = Task<V>>>(&mut self, iter: I) {
+        self.tasks.extend(iter.into_iter().map(|t| (*t.uuid(), t)))
+    }
+}
+
+/// Find cycles in the dependencies of the given tasks
+///
+/// Each cycle is returned as the uuids of the tasks involved, where every task depends on the next
+/// one and the last one depends on the first one. Dependencies on tasks which are not in `tasks`
+/// are ignored. Taskwarrior itself does not allow cycles, but imported or merged data may contain
+/// them.
+pub fn find_dependency_cycles<V: TaskWarriorVersion>(tasks: &[Task<V>]) -> Vec<Vec<Uuid>> {
+    find_cycles(tasks.iter().map(|task| {
+        let depends = task.depends().map_or(&[][..], Vec::as_slice);
+        (*task.uuid(), depends)
+    }))
+}
+
+/// Sort tasks so that every task comes after the tasks it depends on
+///
+/// Apart from that, the order of `tasks` is kept. Dependencies on tasks which are not in `tasks`
+/// are ignored. If the dependencies contain a cycle, an `Error::DependencyCycle` with the uuids of
+/// the tasks in the cycle is returned.
+pub fn topo_sort<V: TaskWarriorVersion>(tasks: &[Task<V>]) -> Result<Vec<&Task<V>>, Error> {
+    fn visit<'a, V: TaskWarriorVersion>(
+        task: &'a Task<V>,
+        by_uuid: &HashMap<Uuid, &'a Task<V>>,
+        visited: &mut HashSet<Uuid>,
+        sorted: &mut Vec<&'a Task<V>>,
+    ) {
+        if !visited.insert(*task.uuid()) {
+            return;
+        }
+        for dependency in task.depends().into_iter().flatten() {
+            if let Some(dependency) = by_uuid.get(dependency) {
+                visit(dependency, by_uuid, visited, sorted);
+            }
+        }
+        sorted.push(task);
+    }
+
+    if let Some(cycle) = find_dependency_cycles(tasks).into_iter().next() {
+        return Err(Error::DependencyCycle(cycle));
+    }
+
+    let by_uuid: HashMap<Uuid, &Task<V>> = tasks.iter().map(|t| (*t.uuid(), t)).collect();
+    let mut visited = HashSet::new();
+    let mut sorted = Vec::with_capacity(tasks.len());
+    for task in tasks {
+        visit(task, &by_uuid, &mut visited, &mut sorted);
+    }
+    Ok(sorted)
+}
+
+/// Find cycles with a depth-first search, where a cycle is closed by an edge to a node which is on
+/// the recursion stack. Nodes are visited in the order of `edges`.
+fn find_cycles<'a, I>(edges: I) -> Vec<Vec<Uuid>>
+where
+    I: IntoIterator<Item = (Uuid, &'a [Uuid])>,
+{
+    #[derive(Clone, Copy, PartialEq)]
+    enum State {
+        OnStack,
+        Done,
+    }
+
+    fn visit(
+        node: Uuid,
+        edges: &HashMap<Uuid, &[Uuid]>,
+        states: &mut HashMap<Uuid, State>,
+        stack: &mut Vec<Uuid>,
+        cycles: &mut Vec<Vec<Uuid>>,
+    ) {
+        states.insert(node, State::OnStack);
+        stack.push(node);
+        for next in edges[&node].iter() {
+            if !edges.contains_key(next) {
+                continue;
+            }
+            match states.get(next) {
+                None => visit(*next, edges, states, stack, cycles),
+                Some(State::OnStack) => {
+                    let start = stack.iter().position(|n| n == next).unwrap();
+                    cycles.push(stack[start..].to_vec());
+                }
+                Some(State::Done) => {}
+            }
+        }
+        stack.pop();
+        states.insert(node, State::Done);
+    }
+
+    let order: Vec<(Uuid, &[Uuid])> = edges.into_iter().collect();
+    let edges: HashMap<Uuid, &[Uuid]> = order.iter().copied().collect();
+    let mut states = HashMap::new();
+    let mut stack = Vec::new();
+    let mut cycles = Vec::new();
+    for (node, _) in order {
+        if !states.contains_key(&node) {
+            visit(node, &edges, &mut states, &mut stack, &mut cycles);
+        }
+    }
+    cycles
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use uuid::{uuid, Uuid};
+
+    use crate::error::Error;
+    use crate::store::{find_dependency_cycles, topo_sort, DependencyRef, TaskByUuid, TaskStore};
+    use crate::task::{Task, TaskBuilder, TW26};
+
+    const A: Uuid = uuid!("8ca953d5-18b4-4eb9-bd56-18f2e5b752f0");
+    const B: Uuid = uuid!("54d49ffc-a06b-4dd8-b7d1-db5f50594312");
+    const C: Uuid = uuid!("08ee8dce-cb97-4c8c-9940-c9a440e90119");
+
+    fn mktask(uuid: Uuid, depends: &[Uuid]) -> Task<TW26> {
+        TaskBuilder::<TW26>::default()
+            .description(uuid.to_string())
+            .uuid(uuid)
+            .depends(depends.to_vec())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_store_chain() {
+        let store: TaskStore = vec![mktask(A, &[B]), mktask(B, &[C]), mktask(C, &[])]
+            .into_iter()
+            .collect();
+
+        assert_eq!(store.len(), 3);
+        assert_eq!(store.get(&B).map(|t| *t.uuid()), Some(B));
+
+        let a = store.get(&A).unwrap();
+        let depends: Vec<&Uuid> = store.resolve_depends(a).iter().map(|t| t.uuid()).collect();
+        assert_eq!(depends, [&B]);
+        assert!(store.resolve_depends(store.get(&C).unwrap()).is_empty());
+
+        assert!(store.dependency_cycles().is_empty());
+    }
+
+    #[test]
+    fn test_store_cycle() {
+        let mut store = TaskStore::new();
+        store.insert(mktask(A, &[B]));
+        store.insert(mktask(B, &[C]));
+        store.insert(mktask(C, &[A]));
+
+        let cycles = store.dependency_cycles();
+        assert_eq!(cycles.len(), 1);
+        let mut cycle = cycles[0].clone();
+        cycle.sort();
+        let mut expected = vec![A, B, C];
+        expected.sort();
+        assert_eq!(cycle, expected);
+    }
+
+    #[test]
+    fn test_depends_resolved() {
+        let store: TaskStore = vec![mktask(B, &[])].into_iter().collect();
+        let a = mktask(A, &[B, C]);
+
+        let depends = a.depends_resolved(&store);
+        assert_eq!(
+            depends,
+            vec![
+                DependencyRef::Resolved(store.get(&B).unwrap()),
+                DependencyRef::Dangling(C)
+            ]
+        );
+        assert_eq!(depends[1].uuid(), &C);
+        assert!(store.get(&B).unwrap().depends_resolved(&store).is_empty());
+    }
+
+    #[test]
+    fn test_store_missing_dependency() {
+        let store: TaskStore = vec![mktask(A, &[B])].into_iter().collect();
+        assert!(store.resolve_depends(store.get(&A).unwrap()).is_empty());
+        assert!(store.dependency_cycles().is_empty());
+    }
+
+    #[test]
+    fn test_find_dependency_cycles() {
+        let tasks = vec![mktask(A, &[B]), mktask(B, &[C]), mktask(C, &[A])];
+        assert_eq!(find_dependency_cycles(&tasks), vec![vec![A, B, C]]);
+
+        let tasks = vec![mktask(B, &[C]), mktask(C, &[A]), mktask(A, &[B])];
+        assert_eq!(find_dependency_cycles(&tasks), vec![vec![B, C, A]]);
+    }
+
+    #[test]
+    fn test_find_dependency_cycles_dag() {
+        // A depends on B and C, B depends on C
+        let tasks = vec![mktask(A, &[B, C]), mktask(B, &[C]), mktask(C, &[])];
+        assert!(find_dependency_cycles(&tasks).is_empty());
+    }
+
+    #[test]
+    fn test_topo_sort() {
+        // D is not in the set
+        let d = uuid!("a97ff9be-0f2e-4b9a-a0d3-7a5e0ad1e3c1");
+        let tasks = vec![mktask(A, &[B, d]), mktask(B, &[C]), mktask(C, &[])];
+
+        let sorted: Vec<Uuid> = topo_sort(&tasks)
+            .unwrap()
+            .into_iter()
+            .map(|t| *t.uuid())
+            .collect();
+        assert_eq!(sorted, vec![C, B, A]);
+    }
+
+    #[test]
+    fn test_topo_sort_cycle() {
+        let tasks = vec![mktask(A, &[B]), mktask(B, &[C]), mktask(C, &[B])];
+        match topo_sort(&tasks) {
+            Err(Error::DependencyCycle(cycle)) => assert_eq!(cycle, vec![B, C]),
+            other => panic!("Expected a dependency cycle, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_task_by_uuid() {
+        let a = mktask(A, &[]);
+        let mut other_a = mktask(A, &[B]);
+        *other_a.description_mut() = "another description".to_owned();
+        let b = mktask(B, &[]);
+
+        let set: HashSet<TaskByUuid> = [&a, &other_a, &b].into_iter().map(TaskByUuid).collect();
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(&TaskByUuid(&other_a)));
+        assert_ne!(a, other_a);
+    }
+}
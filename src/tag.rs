@@ -6,5 +6,197 @@
 
 //! Module containing `Tag` type
 
-/// type definition for tags
-pub type Tag = String;
+use std::borrow::Borrow;
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops::Deref;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+
+/// The virtual tags taskwarrior computes for reports and filters
+pub const VIRTUAL_TAGS: &[&str] = &[
+    "ACTIVE",
+    "ANNOTATED",
+    "BLOCKED",
+    "BLOCKING",
+    "CHILD",
+    "COMPLETED",
+    "DELETED",
+    "DUE",
+    "DUETODAY",
+    "INSTANCE",
+    "LATEST",
+    "MONTH",
+    "ORPHAN",
+    "OVERDUE",
+    "PARENT",
+    "PENDING",
+    "PRIORITY",
+    "PROJECT",
+    "QUARTER",
+    "READY",
+    "SCHEDULED",
+    "TAGGED",
+    "TEMPLATE",
+    "TODAY",
+    "TOMORROW",
+    "UDA",
+    "UNBLOCKED",
+    "UNTIL",
+    "WAITING",
+    "WEEK",
+    "YEAR",
+    "YESTERDAY",
+];
+
+/// A tag of a task
+///
+/// Tags are validated on construction: taskwarrior rejects empty tags, tags containing whitespace
+/// and tags starting with `+` or `-`, which are the operators for adding and removing tags.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Tag(String);
+
+impl Tag {
+    /// Create a new tag, failing with `Error::InvalidTag` if taskwarrior would reject it
+    pub fn new<S: Into<String>>(s: S) -> Result<Tag, Error> {
+        let s = s.into();
+        let valid =
+            !s.is_empty() && !s.starts_with(['+', '-']) && !s.chars().any(char::is_whitespace);
+        if valid {
+            Ok(Tag(s))
+        } else {
+            Err(Error::InvalidTag(s))
+        }
+    }
+
+    /// Get the tag as string slice
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Check whether this is one of the `VIRTUAL_TAGS`, which are computed by taskwarrior
+    ///
+    /// Virtual tags are always uppercase, so `overdue` is a normal tag.
+    pub fn is_virtual(&self) -> bool {
+        VIRTUAL_TAGS.contains(&self.as_str())
+    }
+}
+
+impl Deref for Tag {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for Tag {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for Tag {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Tag {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for Tag {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Tag, Error> {
+        Tag::new(s)
+    }
+}
+
+impl TryFrom<String> for Tag {
+    type Error = Error;
+
+    fn try_from(s: String) -> Result<Tag, Error> {
+        Tag::new(s)
+    }
+}
+
+impl TryFrom<&str> for Tag {
+    type Error = Error;
+
+    fn try_from(s: &str) -> Result<Tag, Error> {
+        Tag::new(s)
+    }
+}
+
+impl From<Tag> for String {
+    fn from(tag: Tag) -> String {
+        tag.0
+    }
+}
+
+impl PartialEq<str> for Tag {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for Tag {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<String> for Tag {
+    fn eq(&self, other: &String) -> bool {
+        self.0 == *other
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::error::Error;
+    use crate::tag::Tag;
+
+    #[test]
+    fn test_valid_tag() {
+        let tag = Tag::new("home").unwrap();
+        assert_eq!(tag, "home");
+        assert_eq!(tag.len(), 4);
+        assert_eq!(tag.to_string(), "home");
+        assert_eq!("ünicode".parse::<Tag>().unwrap(), "ünicode");
+    }
+
+    #[test]
+    fn test_invalid_tag() {
+        for s in ["", "two words", "+home", "-home", "tab\there"] {
+            assert!(
+                matches!(Tag::new(s), Err(Error::InvalidTag(ref t)) if t == s),
+                "{s:?} was accepted"
+            );
+        }
+    }
+
+    #[test]
+    fn test_virtual_tag() {
+        assert!(Tag::new("OVERDUE").unwrap().is_virtual());
+        assert!(!Tag::new("work").unwrap().is_virtual());
+        assert!(!Tag::new("overdue").unwrap().is_virtual());
+    }
+
+    #[test]
+    fn test_tag_serde() {
+        let tags: Vec<Tag> = serde_json::from_str(r#"["a", "b"]"#).unwrap();
+        assert_eq!(tags, ["a", "b"]);
+        assert_eq!(serde_json::to_string(&tags).unwrap(), r#"["a","b"]"#);
+
+        assert!(serde_json::from_str::<Tag>(r#""two words""#).is_err());
+    }
+}
@@ -9,28 +9,36 @@
 use std::marker::PhantomData;
 use std::result::Result as RResult;
 
-use chrono::Utc;
+use chrono::{Duration, Local, NaiveDateTime, TimeZone};
 use serde::{de, Deserialize, Deserializer};
 use serde::{Serialize, Serializer};
+use serde_json::{Map, Value};
 use uuid::Uuid;
 
 use crate::annotation::Annotation;
-use crate::date::Date;
+use crate::clock::{Clock, SystemClock};
+use crate::date::{relative_date, Date, TASKWARRIOR_DATETIME_TEMPLATE};
+use crate::duration::TwDuration;
+use crate::error::{Error, ValidationError};
+use crate::field::FieldValue;
+use crate::mask::Mask;
 use crate::priority::TaskPriority;
 use crate::project::Project;
+use crate::recurrence::Recurrence;
 use crate::status::TaskStatus;
+use crate::store::{DependencyRef, TaskStore};
 use crate::tag::Tag;
-use crate::uda::UDA;
-use crate::urgency::Urgency;
+use crate::uda::{UDAValue, UDA};
+use crate::urgency::{Urgency, UrgencyCoefficients};
 
 /// Unit struct used to represent taskwarrior format 2.6.0 and newer.
 /// See [Task] for more information.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TW26;
 
 /// Unit struct used to represent taskwarrior format 2.5.3 and older.
 /// See [Task] for more information.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TW25;
 
 // Prevents folks outside this crate from implementing their own versions
@@ -65,7 +73,8 @@ impl TaskWarriorVersion for TW25 {}
 /// As of taskwarrior version 2.6.0 and newer, the representation of `depends` has changed from
 /// being a comma seperated string of uuid's to being a proper json array. You can select which
 /// behaviour you want at compiletime by providing either [TW26] (the default) or [TW25] to `Task` as its
-/// type parameter.
+/// type parameter. Deserializing accepts both representations for either version, the version
+/// only decides how `depends` is serialized.
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder, Serialize, Deserialize)]
 #[builder(setter(into))]
 pub struct Task<Version: TaskWarriorVersion + 'static = TW26> {
@@ -81,7 +90,7 @@ pub struct Task<Version: TaskWarriorVersion + 'static = TW26> {
     #[builder(default = "Uuid::new_v4()")]
     uuid: Uuid,
     /// The entry date, when this task was created
-    #[builder(default = "Date::from(Utc::now().naive_utc())")]
+    #[builder(default = "SystemClock.now()")]
     entry: Date,
     /// The description of the task (i.e. its main content)
     /// This field is the only mandatory field, when using the TaskBuilder.
@@ -94,7 +103,7 @@ pub struct Task<Version: TaskWarriorVersion + 'static = TW26> {
     #[builder(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(serialize_with = "serialize_depends::<_, Version>")]
-    #[serde(deserialize_with = "deserialize_depends::<_, Version>", default)]
+    #[serde(deserialize_with = "deserialize_depends", default)]
     depends: Option<Vec<Uuid>>,
     /// The due date of the task
     #[builder(default)]
@@ -107,11 +116,12 @@ pub struct Task<Version: TaskWarriorVersion + 'static = TW26> {
     /// The imask is used internally for recurrence
     #[builder(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
-    imask: Option<f64>,
+    #[serde(deserialize_with = "deserialize_imask", default)]
+    imask: Option<u64>,
     /// The mask is used internally for recurrence
     #[builder(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
-    mask: Option<String>,
+    mask: Option<Mask>,
     /// When the task was last modified
     #[builder(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -164,16 +174,89 @@ pub struct Task<Version: TaskWarriorVersion + 'static = TW26> {
     #[serde(flatten)]
     uda: UDA,
 
+    /// Fields which are neither standard fields nor declared UDAs, kept as they are
+    ///
+    /// This is only filled by `import::import_with_udas()` and the functions alike, plain
+    /// deserializing puts unknown fields into the UDAs.
+    #[builder(default)]
+    #[serde(skip_deserializing)]
+    #[serde(skip_serializing_if = "Map::is_empty")]
+    #[serde(flatten)]
+    extra: Map<String, Value>,
+
     #[doc(hidden)]
     #[builder(setter(skip))]
     #[serde(skip)]
     _version: PhantomData<Version>,
 }
 
-/*
- * TODO: We do not fail if the JSON parsing fails. This panics. We rely on taskwarrior to be nice
- * to us. I guess this should be fixed.
- */
+/// The taskwarrior attribute names of the standard fields of a task
+pub(crate) const STANDARD_FIELDS: [&str; 22] = [
+    "id",
+    "status",
+    "uuid",
+    "entry",
+    "description",
+    "annotations",
+    "depends",
+    "due",
+    "end",
+    "imask",
+    "mask",
+    "modified",
+    "parent",
+    "priority",
+    "project",
+    "recur",
+    "scheduled",
+    "start",
+    "tags",
+    "until",
+    "wait",
+    "urgency",
+];
+
+/// A change of a single field of a task, as reported by `Task::diff()`
+///
+/// The values are rendered as strings, `None` means that the field is not set.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FieldChange {
+    field: String,
+    old: Option<String>,
+    new: Option<String>,
+}
+
+impl FieldChange {
+    /// Get the name of the changed field, which is the UDA name for UDAs
+    pub fn field(&self) -> &str {
+        &self.field
+    }
+
+    /// Get the old value of the field
+    pub fn old_value(&self) -> Option<&str> {
+        self.old.as_deref()
+    }
+
+    /// Get the new value of the field
+    pub fn new_value(&self) -> Option<&str> {
+        self.new.as_deref()
+    }
+}
+
+/// A fix of an inconsistent task, as done by `Task::repair()`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Repair {
+    /// The `end` date of a completed or deleted task was missing and set to the contained date
+    EndSet(Date),
+
+    /// The `end` date of a task which is not completed or deleted was removed, it was the
+    /// contained date
+    EndCleared(Date),
+
+    /// The `start` date of a completed or deleted task was removed, it was the contained date
+    StartCleared(Date),
+}
+
 impl<Version: TaskWarriorVersion> Task<Version> {
     /// Create a new Task instance
     #[allow(clippy::too_many_arguments)]
@@ -189,8 +272,8 @@ impl<Version: TaskWarriorVersion> Task<Version> {
         depends: Option<Vec<Uuid>>,
         due: Option<Date>,
         end: Option<Date>,
-        imask: Option<f64>,
-        mask: Option<String>,
+        imask: Option<u64>,
+        mask: Option<Mask>,
         modified: Option<Date>,
         parent: Option<Uuid>,
         priority: Option<TaskPriority>,
@@ -229,30 +312,111 @@ impl<Version: TaskWarriorVersion> Task<Version> {
             wait,
             urgency,
             uda,
+            extra: Map::new(),
             _version: PhantomData,
         }
     }
 
+    /// Get a `TaskBuilder` to build a new task
+    ///
+    /// ```
+    /// use task_hookrs::task::{Task, TW26};
+    ///
+    /// let task = Task::<TW26>::builder().description("x").build().unwrap();
+    /// assert_eq!(task.description(), "x");
+    /// ```
+    pub fn builder() -> TaskBuilder<Version>
+    where
+        Version: Clone,
+    {
+        TaskBuilder::default()
+    }
+
     /// Get the id of the task
     pub fn id(&self) -> Option<u64> {
         self.id
     }
 
+    /// Remove the id of the task
+    ///
+    /// The id is only a temporary number of the working set of taskwarrior, which is meaningless
+    /// on `task import` and may refer to another task there. Strip it before exporting tasks
+    /// which are imported again later.
+    pub fn strip_id(&mut self) {
+        self.id = None;
+    }
+
     /// Get the status of the task
     pub fn status(&self) -> &TaskStatus {
         &self.status
     }
 
     /// Get the status of the task mutable
+    ///
+    /// Changing the status this way does not update `end` and `start`, so e.g. a task set to
+    /// `Completed` has no `end` date, which taskwarrior rejects. Use `Task::set_status()` instead.
     pub fn status_mut(&mut self) -> &mut TaskStatus {
         &mut self.status
     }
 
+    /// Set the status of the task, keeping the fields which depend on it consistent
+    ///
+    /// Setting `Completed` or `Deleted` stamps `end` with the current time and stops the task, like
+    /// `Task::complete()` and `Task::delete()`. The `end` date is kept if the task already had one
+    /// of these states. Setting any other status removes the `end` date. Note that a `Waiting`
+    /// task also needs a `wait` date, which is not set by this.
+    pub fn set_status(&mut self, status: TaskStatus) {
+        if status.is_terminal() {
+            if !self.status.is_terminal() || self.end.is_none() {
+                self.end = Some(SystemClock.now());
+            }
+            self.start = None;
+        } else {
+            self.end = None;
+        }
+        self.status = status;
+    }
+
+    /// Check whether the status of the task is `Pending`
+    pub fn is_pending(&self) -> bool {
+        self.status == TaskStatus::Pending
+    }
+
+    /// Check whether the status of the task is `Waiting`
+    pub fn is_waiting(&self) -> bool {
+        self.status == TaskStatus::Waiting
+    }
+
+    /// Check whether the status of the task is `Completed`
+    pub fn is_completed(&self) -> bool {
+        self.status == TaskStatus::Completed
+    }
+
+    /// Check whether the status of the task is `Deleted`
+    pub fn is_deleted(&self) -> bool {
+        self.status == TaskStatus::Deleted
+    }
+
+    /// Check whether the status of the task is `Recurring`
+    pub fn is_recurring(&self) -> bool {
+        self.status == TaskStatus::Recurring
+    }
+
+    /// Check whether the task is pending and started, like the `ACTIVE` virtual tag
+    pub fn is_active(&self) -> bool {
+        self.is_pending() && self.start.is_some()
+    }
+
     /// Get the uuid of the task
     pub fn uuid(&self) -> &Uuid {
         &self.uuid
     }
 
+    /// Get the first 8 hex characters of the uuid, as shown by taskwarrior in many reports
+    pub fn uuid_short(&self) -> String {
+        self.uuid.to_string()[..8].to_owned()
+    }
+
     /// Get the uuid of the task mutable
     pub fn uuid_mut(&mut self) -> &mut Uuid {
         &mut self.uuid
@@ -273,6 +437,11 @@ impl<Version: TaskWarriorVersion> Task<Version> {
         &self.description
     }
 
+    /// Get the description of the task as string slice
+    pub fn description_str(&self) -> &str {
+        &self.description
+    }
+
     /// Get the description of the task mutable
     pub fn description_mut(&mut self) -> &mut String {
         &mut self.description
@@ -283,13 +452,18 @@ impl<Version: TaskWarriorVersion> Task<Version> {
         self.annotations.as_ref()
     }
 
+    /// Iterate over the annotations of the task, which yields nothing if there are none
+    pub fn iter_annotations(&self) -> impl Iterator<Item = &Annotation> {
+        self.annotations.iter().flatten()
+    }
+
     /// Get the annotations of the task mutable
     pub fn annotations_mut(&mut self) -> Option<&mut Vec<Annotation>> {
         self.annotations.as_mut()
     }
 
     /// Set annotations
-    pub fn set_annotations<T, A>(&mut self, new: Option<T>)
+    pub fn set_annotations<T>(&mut self, new: Option<T>)
     where
         T: IntoIterator,
         T::Item: Into<Annotation>,
@@ -297,11 +471,36 @@ impl<Version: TaskWarriorVersion> Task<Version> {
         self.annotations = new.map(|x| x.into_iter().map(Into::into).collect());
     }
 
+    /// Add an annotation, keeping the annotations ordered chronologically like taskwarrior shows
+    /// them. The annotation is inserted after all annotations which are not newer.
+    pub fn add_annotation(&mut self, annotation: Annotation) {
+        let annotations = self.annotations.get_or_insert_with(Vec::new);
+        let index = annotations.partition_point(|a| a.entry() <= annotation.entry());
+        annotations.insert(index, annotation);
+    }
+
+    /// Get the annotations which were made at or after `since`, in chronological order
+    pub fn annotations_since(&self, since: &Date) -> Vec<&Annotation> {
+        let mut annotations: Vec<&Annotation> = self
+            .annotations
+            .iter()
+            .flatten()
+            .filter(|a| a.entry() >= since)
+            .collect();
+        annotations.sort_by_key(|a| a.entry());
+        annotations
+    }
+
     /// Get the dependencies of the task
     pub fn depends(&self) -> Option<&Vec<Uuid>> {
         self.depends.as_ref()
     }
 
+    /// Iterate over the dependencies of the task, which yields nothing if there are none
+    pub fn iter_depends(&self) -> impl Iterator<Item = &Uuid> {
+        self.depends.iter().flatten()
+    }
+
     /// Get the dependencies of the task mutable
     pub fn depends_mut(&mut self) -> Option<&mut Vec<Uuid>> {
         self.depends.as_mut()
@@ -316,6 +515,34 @@ impl<Version: TaskWarriorVersion> Task<Version> {
         self.depends = new.map(|x| x.into_iter().map(Into::into).collect());
     }
 
+    /// Look up the dependencies of the task in `store`
+    ///
+    /// Dependencies which are not in the store are returned as `DependencyRef::Dangling`.
+    pub fn depends_resolved<'a>(
+        &self,
+        store: &'a TaskStore<Version>,
+    ) -> Vec<DependencyRef<'a, Version>> {
+        self.depends
+            .iter()
+            .flatten()
+            .map(|uuid| match store.get(uuid) {
+                Some(task) => DependencyRef::Resolved(task),
+                None => DependencyRef::Dangling(*uuid),
+            })
+            .collect()
+    }
+
+    /// Check whether the task depends on a task in `store` which is not completed or deleted
+    ///
+    /// Dependencies which are not in the store do not block the task.
+    pub fn is_blocked(&self, store: &TaskStore<Version>) -> bool {
+        self.depends
+            .iter()
+            .flatten()
+            .filter_map(|uuid| store.get(uuid))
+            .any(|task| !task.status.is_terminal())
+    }
+
     /// Get the due date of the task
     pub fn due(&self) -> Option<&Date> {
         self.due.as_ref()
@@ -334,6 +561,38 @@ impl<Version: TaskWarriorVersion> Task<Version> {
         self.due = new.map(Into::into)
     }
 
+    /// Set the due date to the current time plus the duration
+    ///
+    /// # Panics
+    ///
+    /// Panics if the due date is out of the range of `Date`.
+    pub fn set_due_in(&mut self, duration: TwDuration) {
+        self.set_due_in_with(duration, &SystemClock)
+    }
+
+    /// Set the due date to the current time of `clock` plus the duration, see
+    /// `Task::set_due_in()`
+    pub fn set_due_in_with(&mut self, duration: TwDuration, clock: &dyn Clock) {
+        self.due = Some(Date::from(*clock.now() + *duration));
+    }
+
+    /// Set the due date relative to the current time, like `+7d`, `-2h` or `eow`
+    ///
+    /// Durations with an optional sign and the named dates `now`, `tomorrow`, `eod` and `eow` are
+    /// supported. Fails with `Error::InvalidDuration` if the spec can not be parsed, the due date
+    /// is not changed then.
+    pub fn set_due_relative(&mut self, spec: &str) -> Result<(), Error> {
+        self.set_due_relative_with(spec, &SystemClock)
+    }
+
+    /// Set the due date relative to the current time of `clock`, see `Task::set_due_relative()`
+    pub fn set_due_relative_with(&mut self, spec: &str, clock: &dyn Clock) -> Result<(), Error> {
+        let due = relative_date(spec, &clock.now())
+            .ok_or_else(|| Error::InvalidDuration(spec.to_owned()))?;
+        self.due = Some(due);
+        Ok(())
+    }
+
     /// Get the end date of the task
     pub fn end(&self) -> Option<&Date> {
         self.end.as_ref()
@@ -353,37 +612,37 @@ impl<Version: TaskWarriorVersion> Task<Version> {
     }
 
     /// Get the imask of the task
-    pub fn imask(&self) -> Option<&f64> {
+    pub fn imask(&self) -> Option<&u64> {
         self.imask.as_ref()
     }
 
     /// Get the imask of the task mutable
-    pub fn imask_mut(&mut self) -> Option<&mut f64> {
+    pub fn imask_mut(&mut self) -> Option<&mut u64> {
         self.imask.as_mut()
     }
 
     /// Set imask
     pub fn set_imask<T>(&mut self, new: Option<T>)
     where
-        T: Into<f64>,
+        T: Into<u64>,
     {
         self.imask = new.map(Into::into)
     }
 
     /// Get the mask of the task
-    pub fn mask(&self) -> Option<&String> {
+    pub fn mask(&self) -> Option<&Mask> {
         self.mask.as_ref()
     }
 
     /// Get the mask of the task mutable
-    pub fn mask_mut(&mut self) -> Option<&mut String> {
+    pub fn mask_mut(&mut self) -> Option<&mut Mask> {
         self.mask.as_mut()
     }
 
     /// Set mask
     pub fn set_mask<T>(&mut self, new: Option<T>)
     where
-        T: Into<String>,
+        T: Into<Mask>,
     {
         self.mask = new.map(Into::into)
     }
@@ -429,6 +688,11 @@ impl<Version: TaskWarriorVersion> Task<Version> {
         self.priority.as_ref()
     }
 
+    /// Get the priority of the task as string slice
+    pub fn priority_str(&self) -> Option<&str> {
+        self.priority.as_deref()
+    }
+
     /// Get the priority of the task mutable
     pub fn priority_mut(&mut self) -> Option<&mut TaskPriority> {
         self.priority.as_mut()
@@ -447,6 +711,21 @@ impl<Version: TaskWarriorVersion> Task<Version> {
         self.project.as_ref()
     }
 
+    /// Get the project of the task as string slice
+    pub fn project_str(&self) -> Option<&str> {
+        self.project.as_deref()
+    }
+
+    /// Check whether the task belongs to a project
+    pub fn has_project(&self) -> bool {
+        self.project.is_some()
+    }
+
+    /// Get the project of the task, or `default` if the task has no project
+    pub fn project_or<'a>(&'a self, default: &'a str) -> &'a str {
+        self.project.as_deref().unwrap_or(default)
+    }
+
     /// Get the project of the task mutable
     pub fn project_mut(&mut self) -> Option<&mut Project> {
         self.project.as_mut()
@@ -462,13 +741,22 @@ impl<Version: TaskWarriorVersion> Task<Version> {
 
     /// Get the recur of the task
     ///
-    /// This is exported as String by now. This might change in future versions of this crate.
+    /// This is the raw String, see `Task::recurrence()` for the parsed form.
     pub fn recur(&self) -> Option<&String> {
         self.recur.as_ref()
     }
 
-    /// This is exported as String by now. This might change in future versions of this crate.
-    /// mutable
+    /// Get the recur of the task as string slice
+    pub fn recur_str(&self) -> Option<&str> {
+        self.recur.as_deref()
+    }
+
+    /// Get the recur of the task, parsed as `Recurrence`
+    pub fn recurrence(&self) -> Option<RResult<Recurrence, Error>> {
+        self.recur.as_deref().map(str::parse)
+    }
+
+    /// Get the recur of the task mutable
     pub fn recur_mut(&mut self) -> Option<&mut String> {
         self.recur.as_mut()
     }
@@ -522,6 +810,16 @@ impl<Version: TaskWarriorVersion> Task<Version> {
         self.tags.as_ref()
     }
 
+    /// Iterate over the tags of the task, which yields nothing if there are none
+    pub fn iter_tags(&self) -> impl Iterator<Item = &Tag> {
+        self.tags.iter().flatten()
+    }
+
+    /// Get the tags of the task which are not virtual tags, see `Tag::is_virtual()`
+    pub fn user_tags(&self) -> impl Iterator<Item = &Tag> {
+        self.tags.iter().flatten().filter(|t| !t.is_virtual())
+    }
+
     /// Get the tags of the task mutable
     pub fn tags_mut(&mut self) -> Option<&mut Vec<Tag>> {
         self.tags.as_mut()
@@ -536,6 +834,48 @@ impl<Version: TaskWarriorVersion> Task<Version> {
         self.tags = new.map(|x| x.into_iter().map(Into::into).collect());
     }
 
+    /// Add tags, keeping the tags which are already set
+    ///
+    /// Tags which the task already has are not added again.
+    pub fn add_tags<I>(&mut self, tags: I)
+    where
+        I: IntoIterator,
+        I::Item: Into<Tag>,
+    {
+        for tag in tags.into_iter().map(Into::into) {
+            let existing = self.tags.get_or_insert_with(Vec::new);
+            if !existing.contains(&tag) {
+                existing.push(tag);
+            }
+        }
+    }
+
+    /// Remove all tags
+    ///
+    /// This unsets the tags instead of setting an empty list, so no `tags` are serialized.
+    pub fn clear_tags(&mut self) {
+        self.tags = None;
+    }
+
+    /// Check whether the task has the tag
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().flatten().any(|t| t == tag)
+    }
+
+    /// Check whether the task has at least one of the tags
+    ///
+    /// Returns `false` if no tags are given.
+    pub fn tags_contains_any(&self, tags: &[&str]) -> bool {
+        tags.iter().any(|tag| self.has_tag(tag))
+    }
+
+    /// Check whether the task has all of the tags
+    ///
+    /// Returns `true` if no tags are given.
+    pub fn tags_contains_all(&self, tags: &[&str]) -> bool {
+        tags.iter().all(|tag| self.has_tag(tag))
+    }
+
     /// Get the until date of the task
     pub fn until(&self) -> Option<&Date> {
         self.until.as_ref()
@@ -598,180 +938,923 @@ impl<Version: TaskWarriorVersion> Task<Version> {
     pub fn uda_mut(&mut self) -> &mut UDA {
         &mut self.uda
     }
-}
 
-fn serialize_depends<S, T: 'static>(
-    field: &Option<Vec<Uuid>>,
-    serializer: S,
-) -> RResult<S::Ok, S::Error>
-where
-    S: Serializer,
-{
-    if std::any::TypeId::of::<T>() == std::any::TypeId::of::<TW25>() {
-        let value = field.as_ref().unwrap();
-        let v: Vec<String> = value.iter().map(Uuid::to_string).collect();
-        serializer.serialize_str(&v.join(","))
-    } else {
-        field.serialize(serializer)
+    /// Keep only the UDAs for which `keep` returns `true`
+    ///
+    /// Useful to drop UDAs which are not declared on the taskwarrior instance the task is
+    /// exported to.
+    pub fn retain_udas<F: Fn(&str, &UDAValue) -> bool>(&mut self, keep: F) {
+        self.uda.retain(|name, value| keep(name, value));
     }
-}
 
-fn deserialize_depends<'de, D, T: 'static>(deserializer: D) -> RResult<Option<Vec<Uuid>>, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    if std::any::TypeId::of::<T>() == std::any::TypeId::of::<TW25>() {
-        let raw: String = String::deserialize(deserializer)?;
-        let mut uuids = vec![];
-        for uuid in raw.split(',') {
-            uuids.push(Uuid::parse_str(uuid).map_err(de::Error::custom)?);
-        }
-        Ok(Some(uuids))
-    } else {
-        let value: Option<Vec<Uuid>> = Option::deserialize(deserializer)?;
-        Ok(value)
+    /// Get the fields which are neither standard fields nor declared UDAs
+    ///
+    /// See `import::import_with_udas()`.
+    pub fn extra(&self) -> &Map<String, Value> {
+        &self.extra
     }
-}
-
-#[cfg(test)]
-mod test {
-    use crate::annotation::Annotation;
-    use crate::date::Date;
-    use crate::date::TASKWARRIOR_DATETIME_TEMPLATE;
-    use crate::status::TaskStatus;
-    use crate::task::{Task, TW25, TW26};
-    use crate::uda::UDAValue;
-
-    use chrono::NaiveDateTime;
-    use serde_json;
-    use uuid::{uuid, Uuid};
 
-    fn mklogger() {
-        env_logger::init();
-        log::debug!("Env-logger enabled");
+    /// Get the fields which are neither standard fields nor declared UDAs mutable
+    pub fn extra_mut(&mut self) -> &mut Map<String, Value> {
+        &mut self.extra
     }
 
-    fn mkdate(s: &str) -> Date {
-        let n = NaiveDateTime::parse_from_str(s, TASKWARRIOR_DATETIME_TEMPLATE);
-        Date::from(n.unwrap())
+    /// Get the `estimate` UDA of the task, parsed as `TwDuration`
+    ///
+    /// Returns `None` if there is no estimate or if it is not a string taskwarrior would accept
+    /// as duration, like `30min` or `PT30M`. The UDA itself is left untouched.
+    pub fn estimate(&self) -> Option<TwDuration> {
+        match self.uda.get("estimate") {
+            Some(UDAValue::Str(s)) => s.parse().ok(),
+            _ => None,
+        }
     }
 
-    #[test]
-    fn test_deser() {
-        let s = r#"{
-"id": 1,
-"description": "test",
-"entry": "20150619T165438Z",
-"status": "waiting",
-"uuid": "8ca953d5-18b4-4eb9-bd56-18f2e5b752f0",
-"urgency": 5.3
-}"#;
+    /// Get the changes of all fields which differ between this task and the other task
+    ///
+    /// The changes are ordered like the fields of the task, followed by the changed UDAs ordered
+    /// by their name.
+    pub fn diff(&self, other: &Task<Version>) -> Vec<FieldChange> {
+        let mut changes: Vec<FieldChange> = self
+            .field_strings()
+            .into_iter()
+            .zip(other.field_strings())
+            .filter(|((_, old), (_, new))| old != new)
+            .map(|((field, old), (_, new))| FieldChange {
+                field: field.to_owned(),
+                old,
+                new,
+            })
+            .collect();
+
+        let mut uda_names: Vec<&String> = self.uda.keys().chain(other.uda.keys()).collect();
+        uda_names.sort();
+        uda_names.dedup();
+        for name in uda_names {
+            let old = self.uda.get(name).map(ToString::to_string);
+            let new = other.uda.get(name).map(ToString::to_string);
+            if old != new {
+                changes.push(FieldChange {
+                    field: name.clone(),
+                    old,
+                    new,
+                });
+            }
+        }
+        changes
+    }
 
-        println!("{}", s);
+    /// Compare two tasks, ignoring the fields which are recomputed by taskwarrior
+    ///
+    /// These are the `id`, the `modified` date and the `urgency`. All other fields, including the
+    /// UDAs, are compared.
+    pub fn eq_ignoring_volatile(&self, other: &Task<Version>) -> bool {
+        self.status == other.status
+            && self.uuid == other.uuid
+            && self.entry == other.entry
+            && self.description == other.description
+            && self.annotations == other.annotations
+            && self.depends == other.depends
+            && self.due == other.due
+            && self.end == other.end
+            && self.imask == other.imask
+            && self.mask == other.mask
+            && self.parent == other.parent
+            && self.priority == other.priority
+            && self.project == other.project
+            && self.recur == other.recur
+            && self.scheduled == other.scheduled
+            && self.start == other.start
+            && self.tags == other.tags
+            && self.until == other.until
+            && self.wait == other.wait
+            && self.uda == other.uda
+            && self.extra == other.extra
+    }
+
+    /// Check the invariants taskwarrior enforces for tasks
+    ///
+    /// Parsing a task does not check these, so tasks which did not come from taskwarrior may
+    /// violate them. All violations are returned, not only the first one.
+    pub fn validate(&self) -> RResult<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
 
-        let task = serde_json::from_str(s);
-        println!("{:?}", task);
-        assert!(task.is_ok());
-        let task: Task = task.unwrap();
+        if self.status.is_terminal() && self.end.is_none() {
+            errors.push(ValidationError::MissingEnd(self.status.clone()));
+        }
+        if self.status == TaskStatus::Waiting && self.wait.is_none() {
+            errors.push(ValidationError::MissingWait);
+        }
+        if self.until.is_some() != self.recur.is_some() {
+            errors.push(ValidationError::UntilRecurMismatch);
+        }
+        if self.depends.iter().flatten().any(|d| *d == self.uuid) {
+            errors.push(ValidationError::SelfDependency);
+        }
+        if matches!(self.due, Some(ref due) if *due < self.entry) {
+            errors.push(ValidationError::DueBeforeEntry);
+        }
 
-        assert_eq!(*task.status(), TaskStatus::Waiting);
-        assert_eq!(task.description(), "test");
-        assert_eq!(*task.entry(), mkdate("20150619T165438Z"));
-        assert_eq!(
-            *task.uuid(),
-            Uuid::parse_str("8ca953d5-18b4-4eb9-bd56-18f2e5b752f0").unwrap()
-        );
-        assert_eq!(task.urgency(), Some(&5.3));
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
 
-        let back = serde_json::to_string(&task).unwrap();
+    /// Fix inconsistencies between the status and the `end` and `start` dates
+    ///
+    /// Completed and deleted tasks get an `end` date if they have none, which is the `modified`
+    /// date of the task or the current time if that is not set either, and lose their `start`
+    /// date. Other tasks lose their `end` date. In contrast to `validate()`, this changes the
+    /// task. All changes are returned.
+    pub fn repair(&mut self) -> Vec<Repair> {
+        let mut repairs = Vec::new();
+
+        if self.status.is_terminal() {
+            if self.end.is_none() {
+                let end = self.modified.clone().unwrap_or_else(|| SystemClock.now());
+                self.end = Some(end.clone());
+                repairs.push(Repair::EndSet(end));
+            }
+            if let Some(start) = self.start.take() {
+                repairs.push(Repair::StartCleared(start));
+            }
+        } else if let Some(end) = self.end.take() {
+            repairs.push(Repair::EndCleared(end));
+        }
 
-        assert!(back.contains("description"));
-        assert!(back.contains("test"));
-        assert!(back.contains("entry"));
-        assert!(back.contains("20150619T165438Z"));
-        assert!(back.contains("status"));
-        assert!(back.contains("waiting"));
-        assert!(back.contains("uuid"));
-        assert!(back.contains("urgency"));
-        assert!(back.contains("8ca953d5-18b4-4eb9-bd56-18f2e5b752f0"));
+        repairs
     }
 
-    #[test]
-    fn test_deser_more_tw26() {
-        let s = r#"{
-"id": 1,
-"description": "some description",
-"entry": "20150619T165438Z",
-"modified": "20160327T164007Z",
-"project": "someproject",
-"status": "waiting",
-"tags": ["some", "tags", "are", "here"],
-"uuid": "8ca953d5-18b4-4eb9-bd56-18f2e5b752f0",
-"depends": ["8ca953d5-18b4-4eb9-bd56-18f2e5b752f0","5a04bb1e-3f4b-49fb-b9ba-44407ca223b5"],
-"wait": "20160508T164007Z",
-"urgency": 0.583562
-}"#;
-        let task = serde_json::from_str(s);
-        assert!(task.is_ok());
-        let task: Task = task.unwrap();
+    /// Generate the upcoming instances of a recurring task
+    ///
+    /// This task is the recurring template. Up to `count` child tasks are generated, the first one
+    /// is due at the `due` date of the template, every further one a recurrence later. No child is
+    /// due after `until`. The children get fresh UUIDs, the template as `parent`, their index as
+    /// `imask` and the status `Pending`, just like the children taskwarrior materializes.
+    ///
+    /// If the template has no `due` date or no valid `recur`, no children are generated.
+    pub fn expand_recurrence(&self, count: usize) -> Vec<Task<Version>> {
+        let (due, recurrence) = match (&self.due, self.recurrence()) {
+            (Some(due), Some(Ok(recurrence))) => (due, recurrence),
+            _ => return Vec::new(),
+        };
+
+        (0..count)
+            .map_while(|n| recurrence.occurrence(due, u32::try_from(n).ok()?))
+            .take_while(|date| !matches!(self.until, Some(ref until) if date > until))
+            .enumerate()
+            .map(|(n, date)| Task {
+                id: None,
+                status: TaskStatus::Pending,
+                uuid: Uuid::new_v4(),
+                entry: self.entry.clone(),
+                description: self.description.clone(),
+                annotations: self.annotations.clone(),
+                depends: self.depends.clone(),
+                due: Some(date),
+                end: None,
+                imask: Some(n as u64),
+                mask: None,
+                modified: None,
+                parent: Some(self.uuid),
+                priority: self.priority.clone(),
+                project: self.project.clone(),
+                recur: self.recur.clone(),
+                scheduled: self.scheduled.clone(),
+                start: None,
+                tags: self.tags.clone(),
+                until: self.until.clone(),
+                wait: self.wait.clone(),
+                urgency: None,
+                uda: self.uda.clone(),
+                extra: self.extra.clone(),
+                _version: PhantomData,
+            })
+            .collect()
+    }
+
+    /// Create a copy of this task as a new task, e.g. for using this task as template
+    ///
+    /// The copy gets a new uuid, its status is `Pending` and its entry date is now. `id`, `end`,
+    /// `start`, `modified` and `urgency` are cleared, so the copy can be passed to `task import` as
+    /// fresh task. `parent`, `imask` and `mask` are cleared as well, so a copy of a recurring task
+    /// is not attached to its recurrence. All other fields are copied as they are.
+    pub fn clone_as_new(&self) -> Task<Version> {
+        Task {
+            id: None,
+            status: TaskStatus::Pending,
+            uuid: Uuid::new_v4(),
+            entry: SystemClock.now(),
+            description: self.description.clone(),
+            annotations: self.annotations.clone(),
+            depends: self.depends.clone(),
+            due: self.due.clone(),
+            end: None,
+            imask: None,
+            mask: None,
+            modified: None,
+            parent: None,
+            priority: self.priority.clone(),
+            project: self.project.clone(),
+            recur: self.recur.clone(),
+            scheduled: self.scheduled.clone(),
+            start: None,
+            tags: self.tags.clone(),
+            until: self.until.clone(),
+            wait: self.wait.clone(),
+            urgency: None,
+            uda: self.uda.clone(),
+            extra: self.extra.clone(),
+            _version: PhantomData,
+        }
+    }
 
-        assert_eq!(*task.status(), TaskStatus::Waiting);
-        assert_eq!(task.description(), "some description");
-        assert_eq!(*task.entry(), mkdate("20150619T165438Z"));
-        assert_eq!(
-            *task.uuid(),
-            Uuid::parse_str("8ca953d5-18b4-4eb9-bd56-18f2e5b752f0").unwrap()
-        );
-        assert_eq!(task.urgency(), Some(&0.583562));
-        assert_eq!(task.modified(), Some(&mkdate("20160327T164007Z")));
-        assert_eq!(task.project(), Some(&String::from("someproject")));
+    /// Merge another version of this task into this one
+    ///
+    /// The `modified` dates decide which version is newer. The scalar fields of the newer version
+    /// win, while tags, annotations and dependencies of both versions are combined. UDAs and extra
+    /// fields which are only set in one version are kept, for those set in both versions the newer
+    /// one wins.
+    /// If both versions are equally new, this version wins. The uuid of this task is never changed.
+    pub fn merge(&mut self, other: &Task<Version>) {
+        fn union<T: Clone + PartialEq>(this: &mut Option<Vec<T>>, other: &Option<Vec<T>>) {
+            if let Some(other) = other {
+                let this = this.get_or_insert_with(Vec::new);
+                for item in other {
+                    if !this.contains(item) {
+                        this.push(item.clone());
+                    }
+                }
+            }
+        }
 
-        if let Some(tags) = task.tags() {
-            for tag in tags {
-                let any_tag = ["some", "tags", "are", "here"].iter().any(|t| tag == *t);
-                assert!(any_tag, "Tag {} missing", tag);
+        // None is older than every date
+        if other.modified > self.modified {
+            self.id = other.id;
+            self.status = other.status.clone();
+            self.entry = other.entry.clone();
+            self.description = other.description.clone();
+            self.due = other.due.clone();
+            self.end = other.end.clone();
+            self.imask = other.imask;
+            self.mask = other.mask.clone();
+            self.modified = other.modified.clone();
+            self.parent = other.parent;
+            self.priority = other.priority.clone();
+            self.project = other.project.clone();
+            self.recur = other.recur.clone();
+            self.scheduled = other.scheduled.clone();
+            self.start = other.start.clone();
+            self.until = other.until.clone();
+            self.wait = other.wait.clone();
+            self.urgency = other.urgency;
+            for (name, value) in &other.uda {
+                self.uda.insert(name.clone(), value.clone());
+            }
+            for (name, value) in &other.extra {
+                self.extra.insert(name.clone(), value.clone());
             }
         } else {
-            panic!("Tags completely missing");
+            for (name, value) in &other.uda {
+                self.uda
+                    .entry(name.clone())
+                    .or_insert_with(|| value.clone());
+            }
+            for (name, value) in &other.extra {
+                self.extra
+                    .entry(name.clone())
+                    .or_insert_with(|| value.clone());
+            }
         }
 
-        assert_eq!(task.wait(), Some(&mkdate("20160508T164007Z")));
+        union(&mut self.annotations, &other.annotations);
+        union(&mut self.depends, &other.depends);
+        union(&mut self.tags, &other.tags);
+    }
 
-        if let Some(depends) = task.depends() {
-            assert_eq!(depends.len(), 2);
-            assert!(depends.contains(&uuid!("8ca953d5-18b4-4eb9-bd56-18f2e5b752f0")));
-            assert!(depends.contains(&uuid!("5a04bb1e-3f4b-49fb-b9ba-44407ca223b5")));
-        } else {
-            panic!("Depends completely missing");
+    /// All standard fields as strings, in the order of declaration
+    fn field_strings(&self) -> Vec<(&'static str, Option<String>)> {
+        fn date(d: &Date) -> String {
+            d.format(TASKWARRIOR_DATETIME_TEMPLATE).to_string()
+        }
+        fn join<T: ToString>(v: &[T]) -> String {
+            v.iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(",")
         }
 
-        assert_eq!(task.wait(), Some(&mkdate("20160508T164007Z")));
+        vec![
+            ("id", self.id.map(|id| id.to_string())),
+            ("status", Some(self.status.to_string())),
+            ("uuid", Some(self.uuid.to_string())),
+            ("entry", Some(date(&self.entry))),
+            ("description", Some(self.description.clone())),
+            (
+                "annotations",
+                self.annotations.as_ref().map(|a| {
+                    a.iter()
+                        .map(|a| format!("{} {}", date(a.entry()), a.description()))
+                        .collect::<Vec<_>>()
+                        .join(",")
+                }),
+            ),
+            ("depends", self.depends.as_deref().map(join)),
+            ("due", self.due.as_ref().map(date)),
+            ("end", self.end.as_ref().map(date)),
+            ("imask", self.imask.map(|i| i.to_string())),
+            ("mask", self.mask.as_ref().map(ToString::to_string)),
+            ("modified", self.modified.as_ref().map(date)),
+            ("parent", self.parent.map(|p| p.to_string())),
+            ("priority", self.priority.clone()),
+            ("project", self.project.as_ref().map(ToString::to_string)),
+            ("recur", self.recur.clone()),
+            ("scheduled", self.scheduled.as_ref().map(date)),
+            ("start", self.start.as_ref().map(date)),
+            ("tags", self.tags.as_deref().map(join)),
+            ("until", self.until.as_ref().map(date)),
+            ("wait", self.wait.as_ref().map(date)),
+            ("urgency", self.urgency.map(|u| u.to_string())),
+        ]
+    }
+
+    /// Get a field by its taskwarrior attribute name, e.g. `"description"` or `"due"`
+    ///
+    /// Names which are not standard fields are looked up in the UDAs. `None` is returned if the
+    /// field is not set.
+    pub fn get_field(&self, name: &str) -> Option<FieldValue> {
+        match name {
+            "id" => self.id.map(FieldValue::U64),
+            "status" => Some(FieldValue::Status(self.status.clone())),
+            "uuid" => Some(FieldValue::Uuid(self.uuid)),
+            "entry" => Some(FieldValue::Date(self.entry.clone())),
+            "description" => Some(FieldValue::Str(self.description.clone())),
+            "annotations" => self.annotations.clone().map(FieldValue::Annotations),
+            "depends" => self.depends.clone().map(FieldValue::Uuids),
+            "due" => self.due.clone().map(FieldValue::Date),
+            "end" => self.end.clone().map(FieldValue::Date),
+            "imask" => self.imask.map(FieldValue::U64),
+            "mask" => self.mask.clone().map(FieldValue::Mask),
+            "modified" => self.modified.clone().map(FieldValue::Date),
+            "parent" => self.parent.map(FieldValue::Uuid),
+            "priority" => self.priority.clone().map(FieldValue::Str),
+            "project" => self
+                .project
+                .as_ref()
+                .map(|p| FieldValue::Str(p.to_string())),
+            "recur" => self.recur.clone().map(FieldValue::Str),
+            "scheduled" => self.scheduled.clone().map(FieldValue::Date),
+            "start" => self.start.clone().map(FieldValue::Date),
+            "tags" => self.tags.clone().map(FieldValue::Tags),
+            "until" => self.until.clone().map(FieldValue::Date),
+            "wait" => self.wait.clone().map(FieldValue::Date),
+            "urgency" => self.urgency.map(FieldValue::Urgency),
+            uda => self.uda.get(uda).cloned().map(FieldValue::Uda),
+        }
+    }
+
+    /// Set a field by its taskwarrior attribute name, e.g. `"description"` or `"due"`
+    ///
+    /// Names which are not standard fields are set as UDA. If the value does not have the type of
+    /// the field, e.g. a `FieldValue::Str` for `"due"`, `Error::InvalidFieldValue` is returned and
+    /// the task is left unchanged.
+    pub fn set_field(&mut self, name: &str, value: FieldValue) -> Result<(), Error> {
+        match (name, value) {
+            ("id", FieldValue::U64(v)) => self.id = Some(v),
+            ("status", FieldValue::Status(v)) => self.status = v,
+            ("uuid", FieldValue::Uuid(v)) => self.uuid = v,
+            ("entry", FieldValue::Date(v)) => self.entry = v,
+            ("description", FieldValue::Str(v)) => self.description = v,
+            ("annotations", FieldValue::Annotations(v)) => self.annotations = Some(v),
+            ("depends", FieldValue::Uuids(v)) => self.depends = Some(v),
+            ("due", FieldValue::Date(v)) => self.due = Some(v),
+            ("end", FieldValue::Date(v)) => self.end = Some(v),
+            ("imask", FieldValue::U64(v)) => self.imask = Some(v),
+            ("mask", FieldValue::Mask(v)) => self.mask = Some(v),
+            ("modified", FieldValue::Date(v)) => self.modified = Some(v),
+            ("parent", FieldValue::Uuid(v)) => self.parent = Some(v),
+            ("priority", FieldValue::Str(v)) => self.priority = Some(v),
+            ("project", FieldValue::Str(v)) => self.project = Some(Project::new(v)?),
+            ("recur", FieldValue::Str(v)) => self.recur = Some(v),
+            ("scheduled", FieldValue::Date(v)) => self.scheduled = Some(v),
+            ("start", FieldValue::Date(v)) => self.start = Some(v),
+            ("tags", FieldValue::Tags(v)) => self.tags = Some(v),
+            ("until", FieldValue::Date(v)) => self.until = Some(v),
+            ("wait", FieldValue::Date(v)) => self.wait = Some(v),
+            ("urgency", FieldValue::Urgency(v)) => self.urgency = Some(v),
+            (name, FieldValue::Uda(v)) if !STANDARD_FIELDS.contains(&name) => {
+                self.uda.insert(name.to_owned(), v);
+            }
+            (name, _) => return Err(Error::InvalidFieldValue(name.to_owned())),
+        }
+        Ok(())
+    }
+
+    /// Assemble a task from attribute/value pairs, as printed by `task _get` or `task <id> info`
+    ///
+    /// Dates are accepted in the format taskwarrior exports (`20150619T165438Z`) and in the ISO
+    /// format `task _get` prints in local time (`2015-06-19T16:54:38`). Lists (`tags`, `depends`) are separated
+    /// by commas. Annotations are given as `annotation_<timestamp>` keys, where the timestamp is
+    /// the entry date in seconds since the epoch. All other unknown keys become string UDAs.
+    ///
+    /// `uuid`, `entry` and `status` default to a new uuid, now and `Pending`. A missing
+    /// `description` or a value which can not be parsed for its field is reported as
+    /// `Error::InvalidFieldValue`.
+    pub fn from_dom_pairs(pairs: &[(String, String)]) -> Result<Task<Version>, Error> {
+        fn parse_date(s: &str) -> Option<Date> {
+            if let Ok(utc) = NaiveDateTime::parse_from_str(s, TASKWARRIOR_DATETIME_TEMPLATE) {
+                return Some(Date::from(utc));
+            }
+            let local = NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S").ok()?;
+            let local = Local.from_local_datetime(&local).earliest()?;
+            Some(Date::from(local.naive_utc()))
+        }
+        fn parse_value(name: &str, value: &str) -> Option<FieldValue> {
+            let list = || value.split(',').map(str::trim).filter(|s| !s.is_empty());
+            Some(match name {
+                "id" | "imask" => FieldValue::U64(value.parse().ok()?),
+                "status" => {
+                    FieldValue::Status(serde_json::from_value(value.to_lowercase().into()).ok()?)
+                }
+                "uuid" | "parent" => FieldValue::Uuid(value.parse().ok()?),
+                "entry" | "due" | "end" | "modified" | "scheduled" | "start" | "until" | "wait" => {
+                    FieldValue::Date(parse_date(value)?)
+                }
+                "description" | "priority" | "project" | "recur" => {
+                    FieldValue::Str(value.to_owned())
+                }
+                "depends" => {
+                    FieldValue::Uuids(list().map(str::parse).collect::<RResult<_, _>>().ok()?)
+                }
+                "tags" => FieldValue::Tags(list().map(Tag::new).collect::<RResult<_, _>>().ok()?),
+                "mask" => FieldValue::Mask(value.parse().ok()?),
+                "urgency" => FieldValue::Urgency(Urgency::new(value.parse().ok()?).ok()?),
+                _ => FieldValue::Uda(UDAValue::Str(value.to_owned())),
+            })
+        }
+
+        let mut task = Task {
+            id: None,
+            status: TaskStatus::Pending,
+            uuid: Uuid::new_v4(),
+            entry: SystemClock.now(),
+            description: String::new(),
+            annotations: None,
+            depends: None,
+            due: None,
+            end: None,
+            imask: None,
+            mask: None,
+            modified: None,
+            parent: None,
+            priority: None,
+            project: None,
+            recur: None,
+            scheduled: None,
+            start: None,
+            tags: None,
+            until: None,
+            wait: None,
+            urgency: None,
+            uda: UDA::default(),
+            extra: Map::new(),
+            _version: PhantomData,
+        };
+        let mut has_description = false;
+        for (name, value) in pairs {
+            let invalid = || Error::InvalidFieldValue(name.clone());
+            if let Some(timestamp) = name.strip_prefix("annotation_") {
+                let entry = timestamp
+                    .parse()
+                    .ok()
+                    .and_then(Date::from_timestamp)
+                    .ok_or_else(invalid)?;
+                task.add_annotation(Annotation::new(entry, value.clone()));
+                continue;
+            }
+            if name == "annotations" {
+                return Err(invalid());
+            }
+            has_description |= name == "description";
+            let value = parse_value(name, value).ok_or_else(invalid)?;
+            task.set_field(name, value)?;
+        }
+
+        if has_description {
+            Ok(task)
+        } else {
+            Err(Error::InvalidFieldValue("description".to_owned()))
+        }
+    }
+
+    /// Compute the urgency of the task with the formula taskwarrior uses
+    ///
+    /// This is useful for tasks which did not come from taskwarrior and thus have no `urgency`.
+    /// Without knowledge about the other tasks, all dependencies are assumed to be pending and
+    /// whether the task blocks other tasks is not taken into account. Use
+    /// `Task::urgency_computed_with_store()` to get these terms right.
+    pub fn urgency_computed(&self, coefficients: &UrgencyCoefficients) -> f64 {
+        self.urgency_computed_at(coefficients, &SystemClock.now())
+    }
+
+    /// Compute the urgency of the task at the time `now`, see `Task::urgency_computed()`
+    pub fn urgency_computed_at(&self, coefficients: &UrgencyCoefficients, now: &Date) -> f64 {
+        coefficients.urgency(self, None, now)
+    }
+
+    /// Compute the urgency of the task with the formula taskwarrior uses, looking up the other
+    /// tasks in `store`
+    ///
+    /// The task is blocked if it depends on a task in `store` which is not completed or deleted,
+    /// see `Task::is_blocked()`, and blocking if such a task depends on it.
+    pub fn urgency_computed_with_store(
+        &self,
+        coefficients: &UrgencyCoefficients,
+        store: &TaskStore<Version>,
+    ) -> f64 {
+        self.urgency_computed_with_store_at(coefficients, store, &SystemClock.now())
+    }
+
+    /// Compute the urgency of the task at the time `now`, see
+    /// `Task::urgency_computed_with_store()`
+    pub fn urgency_computed_with_store_at(
+        &self,
+        coefficients: &UrgencyCoefficients,
+        store: &TaskStore<Version>,
+        now: &Date,
+    ) -> f64 {
+        coefficients.urgency(self, Some(store), now)
+    }
+
+    /// Get the time since the task was entered
+    pub fn age(&self) -> Duration {
+        self.age_at(&SystemClock.now())
+    }
+
+    /// Get the time since the task was entered at the time `now`, see `Task::age()`
+    pub fn age_at(&self, now: &Date) -> Duration {
+        **now - *self.entry
+    }
+
+    /// Get the time until the task is due, which is negative for overdue tasks
+    ///
+    /// Returns `None` if the task has no due date.
+    pub fn time_to_due(&self) -> Option<Duration> {
+        self.time_to_due_at(&SystemClock.now())
+    }
+
+    /// Get the time until the task is due at the time `now`, see `Task::time_to_due()`
+    pub fn time_to_due_at(&self, now: &Date) -> Option<Duration> {
+        self.due.as_ref().map(|due| **due - **now)
+    }
+
+    /// Check whether the task is overdue, i.e. it is not completed or deleted and its due date
+    /// has passed
+    pub fn is_overdue(&self) -> bool {
+        self.is_overdue_with(&SystemClock)
+    }
+
+    /// Check whether the task is overdue at the current time of `clock`, see `Task::is_overdue()`
+    pub fn is_overdue_with(&self, clock: &dyn Clock) -> bool {
+        !self.status.is_terminal() && matches!(self.due, Some(ref due) if *due < clock.now())
+    }
+
+    /// Check whether the `wait` date of the task has passed at the time `now`
+    ///
+    /// Returns `false` if the task has no `wait` date.
+    pub fn wait_expired(&self, now: &Date) -> bool {
+        matches!(self.wait, Some(ref wait) if wait <= now)
+    }
+
+    /// Make a waiting task pending if its `wait` date has passed at the time `now`
+    ///
+    /// Like taskwarrior does it, the status becomes `Pending` and the `wait` date is removed.
+    /// Returns whether the task was changed.
+    pub fn promote_if_waited(&mut self, now: &Date) -> bool {
+        if self.status == TaskStatus::Waiting && self.wait_expired(now) {
+            self.status = TaskStatus::Pending;
+            self.wait = None;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Check whether the task is ready at the time `now`, like taskwarrior's `ready` report
+    ///
+    /// A task is ready if it is pending and neither its `wait` nor its `scheduled` date is after
+    /// `now`. Dependencies are not considered, combine this with `Task::is_blocked()` for that.
+    pub fn is_ready(&self, now: &Date) -> bool {
+        self.status == TaskStatus::Pending
+            && !matches!(self.wait, Some(ref wait) if wait > now)
+            && !matches!(self.scheduled, Some(ref scheduled) if scheduled > now)
+    }
+
+    /// Mark the task as completed
+    ///
+    /// This sets the status to `Completed`, stamps `end` with the current time and stops the task
+    /// if it was started, just like `task done` does.
+    pub fn complete(&mut self) {
+        self.status = TaskStatus::Completed;
+        self.end = Some(SystemClock.now());
+        self.start = None;
+    }
+
+    /// Mark the task as deleted
+    ///
+    /// This sets the status to `Deleted`, stamps `end` with the current time and stops the task if
+    /// it was started, just like `task delete` does.
+    pub fn delete(&mut self) {
+        self.status = TaskStatus::Deleted;
+        self.end = Some(SystemClock.now());
+        self.start = None;
+    }
+
+    /// Set the `modified` date to the current time
+    ///
+    /// Taskwarrior uses `modified` to find out which version of a task is the newest, so this
+    /// should be called after changing a task. The `entry` date is not changed.
+    pub fn touch(&mut self) {
+        self.touch_with(&SystemClock)
+    }
+
+    /// Set the `modified` date to the current time of `clock`, see `Task::touch()`
+    pub fn touch_with(&mut self, clock: &dyn Clock) {
+        self.modified = Some(clock.now());
+    }
+
+    /// Start the task, i.e. set the `start` date to the current time
+    ///
+    /// This is named `start_now()` because `start()` is the getter for the start date.
+    /// Starting an already started task keeps the original start date, like `task start` does.
+    pub fn start_now(&mut self) {
+        if self.start.is_none() {
+            self.start = Some(SystemClock.now());
+        }
+    }
+
+    /// Stop the task, i.e. remove the `start` date
+    pub fn stop(&mut self) {
+        self.start = None;
+    }
+
+    /// Move all fields into a task of another version
+    fn into_version<Other: TaskWarriorVersion>(self) -> Task<Other> {
+        Task {
+            id: self.id,
+            status: self.status,
+            uuid: self.uuid,
+            entry: self.entry,
+            description: self.description,
+
+            annotations: self.annotations,
+            depends: self.depends,
+            due: self.due,
+            end: self.end,
+            imask: self.imask,
+            mask: self.mask,
+            modified: self.modified,
+            parent: self.parent,
+            priority: self.priority,
+            project: self.project,
+            recur: self.recur,
+            scheduled: self.scheduled,
+            start: self.start,
+            tags: self.tags,
+            until: self.until,
+            wait: self.wait,
+            urgency: self.urgency,
+            uda: self.uda,
+            extra: self.extra,
+            _version: PhantomData,
+        }
+    }
+}
+
+impl Task<TW25> {
+    /// Convert the task to the format of taskwarrior 2.6.0 and newer
+    ///
+    /// Only the serialization of `depends` differs, so this is lossless.
+    pub fn into_tw26(self) -> Task<TW26> {
+        self.into_version()
+    }
+}
+
+impl Task<TW26> {
+    /// Convert the task to the format of taskwarrior 2.5.3 and older
+    ///
+    /// Only the serialization of `depends` differs, so this is lossless.
+    pub fn into_tw25(self) -> Task<TW25> {
+        self.into_version()
+    }
+}
+
+impl<Version: TaskWarriorVersion> TaskBuilder<Version> {
+    /// Add a single tag, keeping the tags which are already set
+    pub fn with_tag(&mut self, tag: Tag) -> &mut Self {
+        self.tags
+            .get_or_insert(None)
+            .get_or_insert_with(Vec::new)
+            .push(tag);
+        self
+    }
+
+    /// Add a single annotation, keeping the annotations which are already set
+    pub fn with_annotation(&mut self, annotation: Annotation) -> &mut Self {
+        self.annotations
+            .get_or_insert(None)
+            .get_or_insert_with(Vec::new)
+            .push(annotation);
+        self
+    }
+}
+
+fn serialize_depends<S, T: 'static>(
+    field: &Option<Vec<Uuid>>,
+    serializer: S,
+) -> RResult<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    if std::any::TypeId::of::<T>() == std::any::TypeId::of::<TW25>() {
+        let value = field.as_ref().unwrap();
+        let v: Vec<String> = value.iter().map(Uuid::to_string).collect();
+        serializer.serialize_str(&v.join(","))
+    } else {
+        field.serialize(serializer)
+    }
+}
+
+/// Deserialize the dependencies, either as JSON array or as comma separated string
+///
+/// Both forms are accepted for every version, only the serialization depends on the version.
+fn deserialize_depends<'de, D>(deserializer: D) -> RResult<Option<Vec<Uuid>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct DependsVisitor;
+
+    impl<'de> de::Visitor<'de> for DependsVisitor {
+        type Value = Option<Vec<Uuid>>;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            f.write_str("an array of uuids or a string of comma separated uuids")
+        }
+
+        fn visit_none<E: de::Error>(self) -> RResult<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_unit<E: de::Error>(self) -> RResult<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_some<D: Deserializer<'de>>(self, d: D) -> RResult<Self::Value, D::Error> {
+            d.deserialize_any(self)
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> RResult<Self::Value, E> {
+            // Empty segments, e.g. from a trailing comma, are skipped
+            let uuids = v
+                .split(',')
+                .filter(|s| !s.is_empty())
+                .map(|uuid| Uuid::parse_str(uuid).map_err(E::custom))
+                .collect::<RResult<Vec<Uuid>, E>>()?;
+            Ok(Some(uuids).filter(|uuids| !uuids.is_empty()))
+        }
+
+        fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> RResult<Self::Value, A::Error> {
+            let mut uuids = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+            while let Some(uuid) = seq.next_element()? {
+                uuids.push(uuid);
+            }
+            Ok(Some(uuids))
+        }
+    }
+
+    deserializer.deserialize_option(DependsVisitor)
+}
+
+/// Deserialize the imask, which older taskwarrior versions export as float, e.g. `2.0`
+fn deserialize_imask<'de, D>(deserializer: D) -> RResult<Option<u64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct ImaskVisitor;
+
+    impl<'de> de::Visitor<'de> for ImaskVisitor {
+        type Value = Option<u64>;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            f.write_str("a non-negative integer")
+        }
+
+        fn visit_none<E: de::Error>(self) -> RResult<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_unit<E: de::Error>(self) -> RResult<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_some<D: Deserializer<'de>>(self, d: D) -> RResult<Self::Value, D::Error> {
+            d.deserialize_any(self)
+        }
+
+        fn visit_u64<E: de::Error>(self, v: u64) -> RResult<Self::Value, E> {
+            Ok(Some(v))
+        }
+
+        fn visit_i64<E: de::Error>(self, v: i64) -> RResult<Self::Value, E> {
+            u64::try_from(v)
+                .map(Some)
+                .map_err(|_| E::invalid_value(de::Unexpected::Signed(v), &self))
+        }
+
+        fn visit_f64<E: de::Error>(self, v: f64) -> RResult<Self::Value, E> {
+            // 2^64 is the first float which is too large for an u64
+            if v.fract() == 0.0 && (0.0..18_446_744_073_709_551_616.0).contains(&v) {
+                Ok(Some(v as u64))
+            } else {
+                Err(E::invalid_value(de::Unexpected::Float(v), &self))
+            }
+        }
+    }
+
+    deserializer.deserialize_option(ImaskVisitor)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::annotation::Annotation;
+    use crate::date::Date;
+    use crate::date::TASKWARRIOR_DATETIME_TEMPLATE;
+    use crate::mask::{Mask, MaskState};
+    use crate::project::Project;
+    use crate::status::TaskStatus;
+    use crate::tag::Tag;
+    use crate::task::{Repair, Task, TW25, TW26};
+    use crate::uda::UDAValue;
+    use crate::urgency::Urgency;
+
+    use chrono::NaiveDateTime;
+    use serde_json;
+    use uuid::{uuid, Uuid};
+
+    fn mklogger() {
+        env_logger::init();
+        log::debug!("Env-logger enabled");
+    }
+
+    fn mkdate(s: &str) -> Date {
+        let n = NaiveDateTime::parse_from_str(s, TASKWARRIOR_DATETIME_TEMPLATE);
+        Date::from(n.unwrap())
+    }
+
+    #[test]
+    fn test_deser() {
+        let s = r#"{
+"id": 1,
+"description": "test",
+"entry": "20150619T165438Z",
+"status": "waiting",
+"uuid": "8ca953d5-18b4-4eb9-bd56-18f2e5b752f0",
+"urgency": 5.3
+}"#;
+
+        println!("{}", s);
+
+        let task = serde_json::from_str(s);
+        println!("{:?}", task);
+        assert!(task.is_ok());
+        let task: Task = task.unwrap();
+
+        assert_eq!(*task.status(), TaskStatus::Waiting);
+        assert_eq!(task.description(), "test");
+        assert_eq!(*task.entry(), mkdate("20150619T165438Z"));
+        assert_eq!(
+            *task.uuid(),
+            Uuid::parse_str("8ca953d5-18b4-4eb9-bd56-18f2e5b752f0").unwrap()
+        );
+        assert_eq!(*task.urgency().unwrap(), 5.3);
 
         let back = serde_json::to_string(&task).unwrap();
 
         assert!(back.contains("description"));
-        assert!(back.contains("some description"));
+        assert!(back.contains("test"));
         assert!(back.contains("entry"));
         assert!(back.contains("20150619T165438Z"));
-        assert!(back.contains("project"));
-        assert!(back.contains("someproject"));
         assert!(back.contains("status"));
         assert!(back.contains("waiting"));
-        assert!(back.contains("tags"));
-        assert!(back.contains("some"));
-        assert!(back.contains("tags"));
-        assert!(back.contains("are"));
-        assert!(back.contains("here"));
         assert!(back.contains("uuid"));
+        assert!(back.contains("urgency"));
         assert!(back.contains("8ca953d5-18b4-4eb9-bd56-18f2e5b752f0"));
-        assert!(back.contains(
-            r#"["8ca953d5-18b4-4eb9-bd56-18f2e5b752f0","5a04bb1e-3f4b-49fb-b9ba-44407ca223b5"]"#,
-        ));
     }
 
     #[test]
-    fn test_deser_more_tw25() {
-        mklogger();
+    fn test_deser_more_tw26() {
         let s = r#"{
 "id": 1,
 "description": "some description",
@@ -781,17 +1864,13 @@ mod test {
 "status": "waiting",
 "tags": ["some", "tags", "are", "here"],
 "uuid": "8ca953d5-18b4-4eb9-bd56-18f2e5b752f0",
-"depends": "8ca953d5-18b4-4eb9-bd56-18f2e5b752f0,5a04bb1e-3f4b-49fb-b9ba-44407ca223b5",
+"depends": ["8ca953d5-18b4-4eb9-bd56-18f2e5b752f0","5a04bb1e-3f4b-49fb-b9ba-44407ca223b5"],
 "wait": "20160508T164007Z",
 "urgency": 0.583562
 }"#;
-
-        println!("{}", s);
-
         let task = serde_json::from_str(s);
-        println!("{:?}", task);
         assert!(task.is_ok());
-        let task: Task<TW25> = task.unwrap();
+        let task: Task = task.unwrap();
 
         assert_eq!(*task.status(), TaskStatus::Waiting);
         assert_eq!(task.description(), "some description");
@@ -800,9 +1879,9 @@ mod test {
             *task.uuid(),
             Uuid::parse_str("8ca953d5-18b4-4eb9-bd56-18f2e5b752f0").unwrap()
         );
-        assert_eq!(task.urgency(), Some(&0.583562));
+        assert_eq!(*task.urgency().unwrap(), 0.583562);
         assert_eq!(task.modified(), Some(&mkdate("20160327T164007Z")));
-        assert_eq!(task.project(), Some(&String::from("someproject")));
+        assert_eq!(task.project().unwrap(), "someproject");
 
         if let Some(tags) = task.tags() {
             for tag in tags {
@@ -813,6 +1892,8 @@ mod test {
             panic!("Tags completely missing");
         }
 
+        assert_eq!(task.wait(), Some(&mkdate("20160508T164007Z")));
+
         if let Some(depends) = task.depends() {
             assert_eq!(depends.len(), 2);
             assert!(depends.contains(&uuid!("8ca953d5-18b4-4eb9-bd56-18f2e5b752f0")));
@@ -840,30 +1921,171 @@ mod test {
         assert!(back.contains("here"));
         assert!(back.contains("uuid"));
         assert!(back.contains("8ca953d5-18b4-4eb9-bd56-18f2e5b752f0"));
-        assert!(
-            back.contains(
-                "8ca953d5-18b4-4eb9-bd56-18f2e5b752f0,5a04bb1e-3f4b-49fb-b9ba-44407ca223b5",
-            )
-        );
+        assert!(back.contains(
+            r#"["8ca953d5-18b4-4eb9-bd56-18f2e5b752f0","5a04bb1e-3f4b-49fb-b9ba-44407ca223b5"]"#,
+        ));
     }
 
     #[test]
-    fn test_deser_annotation() {
+    fn test_deser_more_tw25() {
+        mklogger();
         let s = r#"{
-"id":192,
-"description":"Some long description for a task",
-"entry":"20160423T125820Z",
-"modified":"20160423T125942Z",
-"project":"project",
-"status":"pending",
-"tags":["search","things"],
-"uuid":"5a04bb1e-3f4b-49fb-b9ba-44407ca223b5",
-"annotations":[{"entry":"20160423T125911Z","description":"An Annotation"},
-               {"entry":"20160423T125926Z","description":"Another Annotation"},
-               {"entry":"20160422T125942Z","description":"A Third Anno"}
-               ],
-"urgency": -5
-}"#;
+"id": 1,
+"description": "some description",
+"entry": "20150619T165438Z",
+"modified": "20160327T164007Z",
+"project": "someproject",
+"status": "waiting",
+"tags": ["some", "tags", "are", "here"],
+"uuid": "8ca953d5-18b4-4eb9-bd56-18f2e5b752f0",
+"depends": "8ca953d5-18b4-4eb9-bd56-18f2e5b752f0,5a04bb1e-3f4b-49fb-b9ba-44407ca223b5",
+"wait": "20160508T164007Z",
+"urgency": 0.583562
+}"#;
+
+        println!("{}", s);
+
+        let task = serde_json::from_str(s);
+        println!("{:?}", task);
+        assert!(task.is_ok());
+        let task: Task<TW25> = task.unwrap();
+
+        assert_eq!(*task.status(), TaskStatus::Waiting);
+        assert_eq!(task.description(), "some description");
+        assert_eq!(*task.entry(), mkdate("20150619T165438Z"));
+        assert_eq!(
+            *task.uuid(),
+            Uuid::parse_str("8ca953d5-18b4-4eb9-bd56-18f2e5b752f0").unwrap()
+        );
+        assert_eq!(*task.urgency().unwrap(), 0.583562);
+        assert_eq!(task.modified(), Some(&mkdate("20160327T164007Z")));
+        assert_eq!(task.project().unwrap(), "someproject");
+
+        if let Some(tags) = task.tags() {
+            for tag in tags {
+                let any_tag = ["some", "tags", "are", "here"].iter().any(|t| tag == *t);
+                assert!(any_tag, "Tag {} missing", tag);
+            }
+        } else {
+            panic!("Tags completely missing");
+        }
+
+        if let Some(depends) = task.depends() {
+            assert_eq!(depends.len(), 2);
+            assert!(depends.contains(&uuid!("8ca953d5-18b4-4eb9-bd56-18f2e5b752f0")));
+            assert!(depends.contains(&uuid!("5a04bb1e-3f4b-49fb-b9ba-44407ca223b5")));
+        } else {
+            panic!("Depends completely missing");
+        }
+
+        assert_eq!(task.wait(), Some(&mkdate("20160508T164007Z")));
+
+        let back = serde_json::to_string(&task).unwrap();
+
+        assert!(back.contains("description"));
+        assert!(back.contains("some description"));
+        assert!(back.contains("entry"));
+        assert!(back.contains("20150619T165438Z"));
+        assert!(back.contains("project"));
+        assert!(back.contains("someproject"));
+        assert!(back.contains("status"));
+        assert!(back.contains("waiting"));
+        assert!(back.contains("tags"));
+        assert!(back.contains("some"));
+        assert!(back.contains("tags"));
+        assert!(back.contains("are"));
+        assert!(back.contains("here"));
+        assert!(back.contains("uuid"));
+        assert!(back.contains("8ca953d5-18b4-4eb9-bd56-18f2e5b752f0"));
+        assert!(
+            back.contains(
+                "8ca953d5-18b4-4eb9-bd56-18f2e5b752f0,5a04bb1e-3f4b-49fb-b9ba-44407ca223b5",
+            )
+        );
+    }
+
+    #[test]
+    fn test_deser_depends_tw25() {
+        fn depends(json: &str) -> Option<Vec<Uuid>> {
+            let s = format!(
+                r#"{{"description":"d","entry":"20150619T165438Z","status":"pending","uuid":"8ca953d5-18b4-4eb9-bd56-18f2e5b752f0"{}}}"#,
+                json
+            );
+            let task: Task<TW25> = serde_json::from_str(&s).unwrap();
+            task.depends().cloned()
+        }
+        let a = uuid!("54d49ffc-a06b-4dd8-b7d1-db5f50594312");
+        let b = uuid!("08ee8dce-cb97-4c8c-9940-c9a440e90119");
+
+        assert_eq!(depends(""), None);
+        assert_eq!(depends(r#","depends":"""#), None);
+        assert_eq!(depends(r#","depends":null"#), None);
+        assert_eq!(
+            depends(r#","depends":"54d49ffc-a06b-4dd8-b7d1-db5f50594312""#),
+            Some(vec![a])
+        );
+        assert_eq!(
+            depends(r#","depends":"54d49ffc-a06b-4dd8-b7d1-db5f50594312,""#),
+            Some(vec![a])
+        );
+        assert_eq!(
+            depends(
+                r#","depends":"54d49ffc-a06b-4dd8-b7d1-db5f50594312,,08ee8dce-cb97-4c8c-9940-c9a440e90119""#
+            ),
+            Some(vec![a, b])
+        );
+    }
+
+    #[test]
+    fn test_deser_depends_mixed_up_versions() {
+        let a = uuid!("54d49ffc-a06b-4dd8-b7d1-db5f50594312");
+        let b = uuid!("08ee8dce-cb97-4c8c-9940-c9a440e90119");
+        let task = |depends: &str| {
+            format!(
+                r#"{{"description":"d","entry":"20150619T165438Z","status":"pending","uuid":"8ca953d5-18b4-4eb9-bd56-18f2e5b752f0","depends":{}}}"#,
+                depends
+            )
+        };
+        let array = task(
+            r#"["54d49ffc-a06b-4dd8-b7d1-db5f50594312","08ee8dce-cb97-4c8c-9940-c9a440e90119"]"#,
+        );
+        let joined =
+            task(r#""54d49ffc-a06b-4dd8-b7d1-db5f50594312,08ee8dce-cb97-4c8c-9940-c9a440e90119""#);
+
+        let tw25: Task<TW25> = serde_json::from_str(&array).unwrap();
+        assert_eq!(tw25.depends(), Some(&vec![a, b]));
+        let tw26: Task<TW26> = serde_json::from_str(&joined).unwrap();
+        assert_eq!(tw26.depends(), Some(&vec![a, b]));
+
+        // Serialization still follows the version
+        assert!(serde_json::to_string(&tw25)
+            .unwrap()
+            .contains(&format!(r#""depends":"{},{}""#, a, b)));
+        assert!(serde_json::to_string(&tw26)
+            .unwrap()
+            .contains(&format!(r#""depends":["{}","{}"]"#, a, b)));
+
+        assert!(serde_json::from_str::<Task<TW26>>(&task(r#"["nope"]"#)).is_err());
+        assert!(serde_json::from_str::<Task<TW25>>(&task("3")).is_err());
+    }
+
+    #[test]
+    fn test_deser_annotation() {
+        let s = r#"{
+"id":192,
+"description":"Some long description for a task",
+"entry":"20160423T125820Z",
+"modified":"20160423T125942Z",
+"project":"project",
+"status":"pending",
+"tags":["search","things"],
+"uuid":"5a04bb1e-3f4b-49fb-b9ba-44407ca223b5",
+"annotations":[{"entry":"20160423T125911Z","description":"An Annotation"},
+               {"entry":"20160423T125926Z","description":"Another Annotation"},
+               {"entry":"20160422T125942Z","description":"A Third Anno"}
+               ],
+"urgency": -5
+}"#;
 
         println!("{}", s);
 
@@ -872,9 +2094,9 @@ mod test {
         assert!(task.is_ok());
         let task: Task = task.unwrap();
 
-        assert_eq!(task.urgency(), Some(&-5.0));
+        assert_eq!(*task.urgency().unwrap(), -5.0);
 
-        let all_annotations = vec![
+        let all_annotations = [
             Annotation::new(mkdate("20160423T125911Z"), String::from("An Annotation")),
             Annotation::new(
                 mkdate("20160423T125926Z"),
@@ -897,6 +2119,53 @@ mod test {
             panic!("Annotations missing");
         }
     }
+    #[test]
+    fn test_deser_recurrence_mask() {
+        let parent = r#"{
+"description":"take out the trash",
+"entry":"20160423T125820Z",
+"status":"recurring",
+"uuid":"5a04bb1e-3f4b-49fb-b9ba-44407ca223b5",
+"due":"20160425T060000Z",
+"recur":"weekly",
+"mask":"+-W"
+}"#;
+        let parent: Task = serde_json::from_str(parent).unwrap();
+        let mask = parent.mask().unwrap();
+        assert_eq!(
+            mask.states(),
+            &[MaskState::Completed, MaskState::Pending, MaskState::Waiting]
+        );
+        assert!(serde_json::to_string(&parent)
+            .unwrap()
+            .contains(r#""mask":"+-W""#));
+
+        let child = r#"{
+"description":"take out the trash",
+"entry":"20160423T125820Z",
+"status":"pending",
+"uuid":"8ca953d5-18b4-4eb9-bd56-18f2e5b752f0",
+"parent":"5a04bb1e-3f4b-49fb-b9ba-44407ca223b5",
+"due":"20160502T060000Z",
+"recur":"weekly",
+"imask":1
+}"#;
+        let child: Task = serde_json::from_str(child).unwrap();
+        assert_eq!(child.imask(), Some(&1));
+        assert_eq!(mask.get(*child.imask().unwrap()), Some(MaskState::Pending));
+        assert!(serde_json::to_string(&child)
+            .unwrap()
+            .contains(r#""imask":1,"#));
+
+        // Older taskwarrior versions export the imask as float
+        let child: Task =
+            serde_json::from_str(r#"{"description":"d","entry":"20160423T125820Z","status":"pending","uuid":"8ca953d5-18b4-4eb9-bd56-18f2e5b752f0","imask":2.0}"#).unwrap();
+        assert_eq!(child.imask(), Some(&2));
+
+        assert!(serde_json::from_str::<Task>(r#"{"description":"d","entry":"20160423T125820Z","status":"pending","uuid":"8ca953d5-18b4-4eb9-bd56-18f2e5b752f0","imask":2.5}"#).is_err());
+        assert!(serde_json::from_str::<Task>(r#"{"description":"d","entry":"20160423T125820Z","status":"pending","uuid":"8ca953d5-18b4-4eb9-bd56-18f2e5b752f0","mask":"+?"}"#).is_err());
+    }
+
     #[test]
     fn test_uda() {
         let s = r#"{
@@ -1028,8 +2297,8 @@ mod test {
             .entry(mkdate("20150619T165438Z"))
             .id(192)
             .modified(mkdate("20160423T125942Z"))
-            .project("project".to_owned())
-            .tags(vec!["search".to_owned(), "things".to_owned()])
+            .project(Project::new("project").unwrap())
+            .tags(tags(&["search", "things"]))
             .uda(uda)
             .build();
         println!("{:?}", t);
@@ -1067,6 +2336,82 @@ mod test {
         assert!(TaskBuilder::<TW25>::default().build().is_err());
     }
 
+    #[test]
+    fn test_complete() {
+        use crate::task::TaskBuilder;
+
+        let mut t = TaskBuilder::<TW26>::default()
+            .description("test")
+            .start(mkdate("20150619T165438Z"))
+            .build()
+            .unwrap();
+        t.complete();
+
+        assert_eq!(t.status(), &TaskStatus::Completed);
+        assert!(t.start().is_none());
+        assert!(t.end().is_some());
+    }
+
+    #[test]
+    fn test_delete() {
+        use crate::task::TaskBuilder;
+
+        let mut t = TaskBuilder::<TW26>::default()
+            .description("test")
+            .start(mkdate("20150619T165438Z"))
+            .build()
+            .unwrap();
+        t.delete();
+
+        assert_eq!(t.status(), &TaskStatus::Deleted);
+        assert!(t.start().is_none());
+        assert!(t.end().is_some());
+    }
+
+    #[test]
+    fn test_set_status() {
+        let mut t = valid_task();
+        t.set_start(Some(mkdate("20150619T170000Z")));
+
+        t.set_status(TaskStatus::Completed);
+        assert_eq!(t.status(), &TaskStatus::Completed);
+        assert!(t.end().is_some());
+        assert!(t.start().is_none());
+        assert_eq!(t.validate(), Ok(()));
+
+        let end = t.end().cloned();
+        t.set_status(TaskStatus::Deleted);
+        assert_eq!(t.end().cloned(), end);
+
+        t.set_status(TaskStatus::Pending);
+        assert_eq!(t.status(), &TaskStatus::Pending);
+        assert!(t.end().is_none());
+    }
+
+    #[test]
+    fn test_start_stop() {
+        use crate::task::TaskBuilder;
+
+        let mut t = TaskBuilder::<TW26>::default()
+            .description("test")
+            .build()
+            .unwrap();
+
+        t.start_now();
+        assert_eq!(t.status(), &TaskStatus::Pending);
+        assert!(t.start().is_some());
+        assert!(t.end().is_none());
+
+        let started = t.start().cloned();
+        t.start_now();
+        assert_eq!(t.start().cloned(), started);
+
+        t.stop();
+        assert_eq!(t.status(), &TaskStatus::Pending);
+        assert!(t.start().is_none());
+        assert!(t.end().is_none());
+    }
+
     const FIELD_NAMES_TO_NOT_SERIALIZE: [&str; 20] = [
         r#""id":"#,
         r#"""annotations:""#,
@@ -1131,4 +2476,1285 @@ mod test {
             );
         }
     }
+
+    #[test]
+    fn test_urgency_computed() {
+        use crate::task::TaskBuilder;
+        use crate::urgency::UrgencyCoefficients;
+
+        let coefficients = UrgencyCoefficients::default();
+        let now = mkdate("20160423T125820Z");
+
+        let plain = TaskBuilder::<TW26>::default()
+            .description("test")
+            .entry(now.clone())
+            .build()
+            .unwrap();
+        assert_eq!(plain.urgency_computed_at(&coefficients, &now), 0.0);
+
+        // next tag (15.0) + one tag (0.8) + project (1.0) + priority H (6.0)
+        let next = TaskBuilder::<TW26>::default()
+            .description("test")
+            .entry(now.clone())
+            .tags(tags(&["next"]))
+            .project(Project::new("project").unwrap())
+            .priority("H".to_owned())
+            .build()
+            .unwrap();
+        let urgency = next.urgency_computed_at(&coefficients, &now);
+        assert!((urgency - 22.8).abs() < 1e-9, "{}", urgency);
+
+        // due now (12.0 * 0.7333) + active (4.0) + 183 days old (2.0 * 183 / 365)
+        let due = TaskBuilder::<TW26>::default()
+            .description("test")
+            .entry(mkdate("20151023T125820Z"))
+            .due(now.clone())
+            .start(now.clone())
+            .build()
+            .unwrap();
+        let expected = 12.0 * (14.0 * 0.8 / 21.0 + 0.2) + 4.0 + 2.0 * 183.0 / 365.0;
+        let urgency = due.urgency_computed_at(&coefficients, &now);
+        assert!(
+            (urgency - expected).abs() < 1e-9,
+            "{} != {}",
+            urgency,
+            expected
+        );
+
+        // waiting (-3.0) + blocked (-5.0) + overdue for more than a week (12.0)
+        let blocked = TaskBuilder::<TW26>::default()
+            .description("test")
+            .entry(now.clone())
+            .status(TaskStatus::Waiting)
+            .depends(vec![Uuid::nil()])
+            .due(mkdate("20160401T000000Z"))
+            .build()
+            .unwrap();
+        let urgency = blocked.urgency_computed_at(&coefficients, &now);
+        assert!((urgency - 4.0).abs() < 1e-9, "{}", urgency);
+    }
+
+    #[test]
+    fn test_urgency_computed_with_store() {
+        use crate::store::TaskStore;
+        use crate::task::TaskBuilder;
+        use crate::urgency::UrgencyCoefficients;
+
+        let coefficients = UrgencyCoefficients::default();
+        let now = mkdate("20160423T125820Z");
+        let task = |status: TaskStatus, depends: Vec<Uuid>| {
+            TaskBuilder::<TW26>::default()
+                .description("test")
+                .entry(now.clone())
+                .status(status)
+                .depends(depends)
+                .build()
+                .unwrap()
+        };
+
+        let blocking = task(TaskStatus::Pending, vec![]);
+        let blocked = task(TaskStatus::Pending, vec![*blocking.uuid()]);
+        let completed = task(TaskStatus::Completed, vec![]);
+        let unblocked = task(TaskStatus::Pending, vec![*completed.uuid()]);
+        let store: TaskStore = [&blocking, &blocked, &completed, &unblocked]
+            .into_iter()
+            .cloned()
+            .collect();
+        let urgency = |t: &Task| t.urgency_computed_with_store_at(&coefficients, &store, &now);
+
+        // blocking (8.0)
+        assert_eq!(urgency(&blocking), 8.0);
+        // blocked (-5.0)
+        assert_eq!(urgency(&blocked), -5.0);
+        // The only dependency is completed, so neither blocked nor blocking
+        assert_eq!(urgency(&unblocked), 0.0);
+
+        // Without a store, all dependencies count as pending
+        assert_eq!(unblocked.urgency_computed_at(&coefficients, &now), -5.0);
+        assert_eq!(blocking.urgency_computed_at(&coefficients, &now), 0.0);
+    }
+
+    #[test]
+    fn test_diff() {
+        use crate::task::TaskBuilder;
+        use crate::uda::UDA;
+
+        let mut uda = UDA::new();
+        uda.insert("estimate".into(), UDAValue::Str("30min".into()));
+        let old = TaskBuilder::<TW26>::default()
+            .description("test")
+            .entry(mkdate("20150619T165438Z"))
+            .tags(tags(&["a", "b"]))
+            .uda(uda)
+            .build()
+            .unwrap();
+
+        let mut new = old.clone();
+        *new.description_mut() = "changed".to_owned();
+        new.tags_mut().unwrap().push(Tag::new("c").unwrap());
+        new.uda_mut().remove("estimate");
+
+        let changes = old.diff(&new);
+        assert_eq!(changes.len(), 3);
+
+        assert_eq!(changes[0].field(), "description");
+        assert_eq!(changes[0].old_value(), Some("test"));
+        assert_eq!(changes[0].new_value(), Some("changed"));
+
+        assert_eq!(changes[1].field(), "tags");
+        assert_eq!(changes[1].old_value(), Some("a,b"));
+        assert_eq!(changes[1].new_value(), Some("a,b,c"));
+
+        assert_eq!(changes[2].field(), "estimate");
+        assert_eq!(changes[2].old_value(), Some("30min"));
+        assert_eq!(changes[2].new_value(), None);
+
+        assert!(old.diff(&old).is_empty());
+    }
+
+    #[test]
+    fn test_merge() {
+        use crate::task::TaskBuilder;
+
+        let base = TaskBuilder::<TW26>::default()
+            .description("test")
+            .entry(mkdate("20150619T165438Z"))
+            .modified(mkdate("20150619T165438Z"))
+            .tags(tags(&["common"]))
+            .build()
+            .unwrap();
+
+        let mut local = base.clone();
+        local.tags_mut().unwrap().push(Tag::new("local").unwrap());
+        local.set_modified(Some(mkdate("20150620T000000Z")));
+        local.uda_mut().insert("local_uda".into(), UDAValue::U64(1));
+
+        let mut server = base.clone();
+        server.tags_mut().unwrap().push(Tag::new("server").unwrap());
+        *server.description_mut() = "changed on server".to_owned();
+        server.set_modified(Some(mkdate("20150621T000000Z")));
+        server
+            .uda_mut()
+            .insert("server_uda".into(), UDAValue::U64(2));
+
+        let mut merged = local.clone();
+        merged.merge(&server);
+        assert_eq!(merged.description(), "changed on server");
+        assert_eq!(merged.modified(), Some(&mkdate("20150621T000000Z")));
+        assert_eq!(
+            merged.tags().unwrap(),
+            &vec!["common".to_owned(), "local".to_owned(), "server".to_owned()]
+        );
+        assert_eq!(merged.uda().len(), 2);
+
+        // The result does not depend on which side was older
+        let mut reverse = server.clone();
+        reverse.merge(&local);
+        assert_eq!(reverse.description(), "changed on server");
+        assert_eq!(reverse.uda(), merged.uda());
+        assert_eq!(reverse.tags().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_eq_ignoring_volatile() {
+        use crate::task::TaskBuilder;
+
+        let task = TaskBuilder::<TW26>::default()
+            .description("test")
+            .id(1)
+            .modified(mkdate("20150619T165438Z"))
+            .urgency(Urgency::from(1.0))
+            .build()
+            .unwrap();
+
+        let mut other = task.clone();
+        other.set_modified(Some(mkdate("20160619T165438Z")));
+        assert_ne!(task, other);
+        assert!(task.eq_ignoring_volatile(&other));
+
+        let mut other = other.clone();
+        other.set_urgency(Some(2.0));
+        assert!(task.eq_ignoring_volatile(&other));
+
+        *other.description_mut() = "changed".to_owned();
+        assert!(!task.eq_ignoring_volatile(&other));
+    }
+
+    fn tags(names: &[&str]) -> Vec<Tag> {
+        names.iter().map(|n| Tag::new(*n).unwrap()).collect()
+    }
+
+    fn valid_task() -> Task<TW26> {
+        use crate::task::TaskBuilder;
+
+        TaskBuilder::<TW26>::default()
+            .description("test")
+            .entry(mkdate("20150619T165438Z"))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_validate_valid() {
+        let mut task = valid_task();
+        assert_eq!(task.validate(), Ok(()));
+
+        task.complete();
+        assert_eq!(task.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_missing_end() {
+        use crate::error::ValidationError;
+
+        let mut task = valid_task();
+        *task.status_mut() = TaskStatus::Completed;
+        assert_eq!(
+            task.validate(),
+            Err(vec![ValidationError::MissingEnd(TaskStatus::Completed)])
+        );
+
+        *task.status_mut() = TaskStatus::Deleted;
+        assert_eq!(
+            task.validate(),
+            Err(vec![ValidationError::MissingEnd(TaskStatus::Deleted)])
+        );
+    }
+
+    #[test]
+    fn test_validate_missing_wait() {
+        use crate::error::ValidationError;
+
+        let mut task = valid_task();
+        *task.status_mut() = TaskStatus::Waiting;
+        assert_eq!(task.validate(), Err(vec![ValidationError::MissingWait]));
+
+        task.set_wait(Some(mkdate("20150620T165438Z")));
+        assert_eq!(task.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_until_recur() {
+        use crate::error::ValidationError;
+
+        let mut task = valid_task();
+        task.set_until(Some(mkdate("20150620T165438Z")));
+        assert_eq!(
+            task.validate(),
+            Err(vec![ValidationError::UntilRecurMismatch])
+        );
+
+        task.set_until(None::<Date>);
+        task.set_recur(Some("weekly"));
+        assert_eq!(
+            task.validate(),
+            Err(vec![ValidationError::UntilRecurMismatch])
+        );
+
+        task.set_until(Some(mkdate("20150620T165438Z")));
+        assert_eq!(task.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_self_dependency() {
+        use crate::error::ValidationError;
+
+        let mut task = valid_task();
+        let uuid = *task.uuid();
+        task.set_depends::<_, Uuid>(Some(vec![uuid]));
+        assert_eq!(task.validate(), Err(vec![ValidationError::SelfDependency]));
+    }
+
+    #[test]
+    fn test_validate_due_before_entry() {
+        use crate::error::ValidationError;
+
+        let mut task = valid_task();
+        task.set_due(Some(mkdate("20150618T165438Z")));
+        assert_eq!(task.validate(), Err(vec![ValidationError::DueBeforeEntry]));
+    }
+
+    #[test]
+    fn test_validate_all_violations() {
+        use crate::error::ValidationError;
+
+        let mut task = valid_task();
+        *task.status_mut() = TaskStatus::Completed;
+        task.set_due(Some(mkdate("20150618T165438Z")));
+        assert_eq!(
+            task.validate(),
+            Err(vec![
+                ValidationError::MissingEnd(TaskStatus::Completed),
+                ValidationError::DueBeforeEntry,
+            ])
+        );
+    }
+
+    #[test]
+    fn test_recurrence() {
+        use crate::recurrence::Recurrence;
+
+        let mut task = valid_task();
+        assert!(task.recurrence().is_none());
+
+        task.set_recur(Some("2w"));
+        assert_eq!(
+            task.recurrence().unwrap().unwrap(),
+            Recurrence::Duration(chrono::Duration::weeks(2))
+        );
+
+        task.set_recur(Some("sometimes"));
+        assert!(task.recurrence().unwrap().is_err());
+        assert_eq!(task.recur().unwrap(), "sometimes");
+    }
+
+    #[test]
+    fn test_expand_recurrence() {
+        let mut template = valid_task();
+        *template.status_mut() = TaskStatus::Recurring;
+        template.set_recur(Some("weekly"));
+        template.set_due(Some(mkdate("20150620T120000Z")));
+        template.set_mask(Some("---".parse::<Mask>().unwrap()));
+
+        let children = template.expand_recurrence(3);
+        assert_eq!(children.len(), 3);
+        let dues: Vec<&Date> = children.iter().map(|c| c.due().unwrap()).collect();
+        assert_eq!(
+            dues,
+            vec![
+                &mkdate("20150620T120000Z"),
+                &mkdate("20150627T120000Z"),
+                &mkdate("20150704T120000Z"),
+            ]
+        );
+        for (n, child) in children.iter().enumerate() {
+            assert_eq!(child.parent(), Some(template.uuid()));
+            assert_eq!(*child.status(), TaskStatus::Pending);
+            assert_ne!(child.uuid(), template.uuid());
+            assert_eq!(child.imask(), Some(&(n as u64)));
+            assert_eq!(child.mask(), None);
+        }
+
+        template.set_until(Some(mkdate("20150627T120000Z")));
+        assert_eq!(template.expand_recurrence(3).len(), 2);
+
+        template.set_recur(Some("sometimes"));
+        assert!(template.expand_recurrence(3).is_empty());
+    }
+
+    #[test]
+    fn test_clone_as_new() {
+        use crate::task::TaskBuilder;
+
+        let mut task = TaskBuilder::<TW26>::default()
+            .description("test")
+            .entry(mkdate("20150619T165438Z"))
+            .id(3)
+            .project(Project::new("home").unwrap())
+            .build()
+            .unwrap();
+        task.set_modified(Some(mkdate("20150620T120000Z")));
+        task.set_start(Some(mkdate("20150620T120000Z")));
+        task.set_parent(Some(uuid!("8ca953d5-18b4-4eb9-bd56-18f2e5b752f0")));
+        task.set_imask(Some(2u64));
+        task.set_mask(Some(vec![MaskState::Pending]));
+        task.set_urgency(Some(5.3));
+        task.complete();
+
+        let copy = task.clone_as_new();
+        assert_ne!(copy.uuid(), task.uuid());
+        assert_eq!(*copy.status(), TaskStatus::Pending);
+        assert!(copy.entry() > task.entry());
+        assert_eq!(copy.id(), None);
+        assert_eq!(copy.end(), None);
+        assert_eq!(copy.start(), None);
+        assert_eq!(copy.modified(), None);
+        assert_eq!(copy.parent(), None);
+        assert_eq!(copy.imask(), None);
+        assert_eq!(copy.mask(), None);
+        assert_eq!(copy.urgency(), None);
+        assert_eq!(copy.description(), task.description());
+        assert_eq!(copy.project(), task.project());
+    }
+
+    #[test]
+    fn test_get_set_field() {
+        use crate::error::Error;
+        use crate::field::FieldValue;
+
+        let mut task = valid_task();
+        task.uda_mut()
+            .insert("estimate".to_owned(), UDAValue::U64(30));
+
+        assert_eq!(
+            task.get_field("description"),
+            Some(FieldValue::Str("test".to_owned()))
+        );
+        assert_eq!(
+            task.get_field("entry"),
+            Some(FieldValue::Date(mkdate("20150619T165438Z")))
+        );
+        assert_eq!(
+            task.get_field("estimate"),
+            Some(FieldValue::Uda(UDAValue::U64(30)))
+        );
+        assert_eq!(task.get_field("project"), None);
+        assert_eq!(task.get_field("missing"), None);
+
+        task.set_field("project", FieldValue::from("home")).unwrap();
+        assert_eq!(task.project().unwrap(), "home");
+        task.set_field("estimate", FieldValue::Uda(UDAValue::U64(60)))
+            .unwrap();
+        assert_eq!(task.uda().get("estimate"), Some(&UDAValue::U64(60)));
+
+        assert!(matches!(
+            task.set_field("due", FieldValue::from("tomorrow")),
+            Err(Error::InvalidFieldValue(ref name)) if name == "due"
+        ));
+        assert!(task
+            .set_field("due", FieldValue::Uda(UDAValue::Str("x".to_owned())))
+            .is_err());
+        assert_eq!(task.due(), None);
+        assert!(task.uda().get("due").is_none());
+    }
+
+    #[test]
+    fn test_project_or() {
+        let mut task = valid_task();
+        assert!(!task.has_project());
+        assert_eq!(task.project_or("(none)"), "(none)");
+
+        task.set_project(Project::new("home").ok());
+        assert!(task.has_project());
+        assert_eq!(task.project_or("(none)"), "home");
+    }
+
+    #[test]
+    fn test_str_getters() {
+        let mut task = valid_task();
+        assert_eq!(task.description_str(), task.description().as_str());
+        assert_eq!(task.project_str(), None);
+
+        task.set_project(Project::new("home").ok());
+        task.set_priority(Some("H"));
+        task.set_recur(Some("weekly"));
+        assert_eq!(task.project_str(), task.project().map(Project::as_str));
+        assert_eq!(task.priority_str(), Some("H"));
+        assert_eq!(task.recur_str(), Some("weekly"));
+    }
+
+    #[test]
+    fn test_touch() {
+        let mut task = valid_task();
+        task.set_modified(Some(mkdate("20150620T120000Z")));
+
+        task.touch();
+        assert!(task.modified().unwrap() > &mkdate("20150620T120000Z"));
+        assert_eq!(task.entry(), &mkdate("20150619T165438Z"));
+    }
+
+    #[test]
+    fn test_convert_version() {
+        let s = r#"{"description":"d","entry":"20150619T165438Z","status":"pending","uuid":"8ca953d5-18b4-4eb9-bd56-18f2e5b752f0","depends":"54d49ffc-a06b-4dd8-b7d1-db5f50594312,08ee8dce-cb97-4c8c-9940-c9a440e90119"}"#;
+        let task: Task<TW25> = serde_json::from_str(s).unwrap();
+
+        let converted = task.clone().into_tw26();
+        let value = serde_json::to_value(&converted).unwrap();
+        assert_eq!(
+            value["depends"],
+            serde_json::json!([
+                "54d49ffc-a06b-4dd8-b7d1-db5f50594312",
+                "08ee8dce-cb97-4c8c-9940-c9a440e90119"
+            ])
+        );
+        assert_eq!(converted.depends(), task.depends());
+
+        assert_eq!(converted.into_tw25(), task);
+    }
+
+    #[test]
+    fn test_promote_if_waited() {
+        let now = mkdate("20160101T120000Z");
+        let mut task = valid_task();
+        *task.status_mut() = TaskStatus::Waiting;
+        task.set_wait(Some(mkdate("20160101T110000Z")));
+
+        assert!(task.wait_expired(&now));
+        assert!(task.promote_if_waited(&now));
+        assert_eq!(*task.status(), TaskStatus::Pending);
+        assert!(task.wait().is_none());
+        assert!(!task.promote_if_waited(&now));
+    }
+
+    #[test]
+    fn test_promote_if_still_waiting() {
+        let now = mkdate("20160101T120000Z");
+        let mut task = valid_task();
+        *task.status_mut() = TaskStatus::Waiting;
+        task.set_wait(Some(mkdate("20160102T000000Z")));
+
+        assert!(!task.wait_expired(&now));
+        assert!(!task.promote_if_waited(&now));
+        assert_eq!(*task.status(), TaskStatus::Waiting);
+        assert_eq!(task.wait(), Some(&mkdate("20160102T000000Z")));
+    }
+
+    #[test]
+    fn test_set_due_relative() {
+        use crate::clock::FixedClock;
+        use crate::error::Error;
+
+        let clock = FixedClock::new(mkdate("20160101T120000Z"));
+        let mut task = valid_task();
+
+        task.set_due_relative_with("+7d", &clock).unwrap();
+        assert_eq!(task.due(), Some(&mkdate("20160108T120000Z")));
+
+        task.set_due_in_with("2h".parse().unwrap(), &clock);
+        assert_eq!(task.due(), Some(&mkdate("20160101T140000Z")));
+
+        match task.set_due_relative_with("someday", &clock) {
+            Err(Error::InvalidDuration(spec)) => assert_eq!(spec, "someday"),
+            other => panic!("Expected InvalidDuration, got {:?}", other),
+        }
+        assert_eq!(task.due(), Some(&mkdate("20160101T140000Z")));
+    }
+
+    #[test]
+    fn test_is_ready() {
+        let now = mkdate("20160101T120000Z");
+        let mut task = valid_task();
+        assert!(task.is_ready(&now));
+
+        task.set_scheduled(Some(mkdate("20160101T110000Z")));
+        assert!(task.is_ready(&now));
+
+        task.set_scheduled(Some(mkdate("20160102T000000Z")));
+        assert!(!task.is_ready(&now));
+
+        task.set_scheduled(None::<Date>);
+        task.set_wait(Some(mkdate("20160102T000000Z")));
+        assert!(!task.is_ready(&now));
+
+        task.set_wait(None::<Date>);
+        task.complete();
+        assert!(!task.is_ready(&now));
+    }
+
+    #[test]
+    fn test_is_blocked() {
+        use crate::store::TaskStore;
+
+        let mut blocker = valid_task();
+        let mut task = valid_task();
+        task.set_depends::<_, Uuid>(Some([*blocker.uuid(), Uuid::new_v4()]));
+
+        let store: TaskStore = [blocker.clone()].into_iter().collect();
+        assert!(task.is_blocked(&store));
+        assert!(!task.is_blocked(&TaskStore::new()));
+
+        blocker.complete();
+        let store: TaskStore = [blocker].into_iter().collect();
+        assert!(!task.is_blocked(&store));
+    }
+
+    #[test]
+    fn test_is_overdue() {
+        use crate::clock::FixedClock;
+
+        let clock = FixedClock::new(mkdate("20160101T120000Z"));
+        let mut task = valid_task();
+        assert!(!task.is_overdue_with(&clock));
+
+        task.set_due(Some(mkdate("20160101T130000Z")));
+        assert!(!task.is_overdue_with(&clock));
+        assert!(task.is_overdue());
+
+        task.set_due(Some(mkdate("20160101T110000Z")));
+        assert!(task.is_overdue_with(&clock));
+
+        task.complete();
+        assert!(!task.is_overdue_with(&clock));
+    }
+
+    #[test]
+    fn test_touch_with() {
+        use crate::clock::FixedClock;
+
+        let mut task = valid_task();
+        task.touch_with(&FixedClock::new(mkdate("20160101T120000Z")));
+        assert_eq!(task.modified(), Some(&mkdate("20160101T120000Z")));
+    }
+
+    #[test]
+    fn test_uuid_short() {
+        let mut task = valid_task();
+        *task.uuid_mut() = uuid!("8ca953d5-18b4-4eb9-bd56-18f2e5b752f0");
+        assert_eq!(task.uuid_short(), "8ca953d5");
+        assert!(task.uuid().to_string().starts_with(&task.uuid_short()));
+    }
+
+    #[test]
+    fn test_retain_udas() {
+        let mut task = valid_task();
+        task.uda_mut()
+            .insert("x_source".into(), UDAValue::Str("mail".into()));
+        task.uda_mut().insert("x_score".into(), UDAValue::U64(3));
+        task.uda_mut()
+            .insert("estimate".into(), UDAValue::Str("PT1H".into()));
+
+        task.retain_udas(|name, _| !name.starts_with("x_"));
+        assert_eq!(task.uda().len(), 1);
+        assert!(task.uda().contains_key("estimate"));
+    }
+
+    #[test]
+    fn test_strip_id() {
+        let s = r#"{"id":3,"description":"d","entry":"20150619T165438Z","status":"pending","uuid":"8ca953d5-18b4-4eb9-bd56-18f2e5b752f0"}"#;
+        let mut task: Task = serde_json::from_str(s).unwrap();
+        assert_eq!(task.id(), Some(3));
+
+        task.strip_id();
+        assert_eq!(task.id(), None);
+        let value = serde_json::to_value(&task).unwrap();
+        assert!(value.get("id").is_none());
+        assert_eq!(value["description"], "d");
+    }
+
+    #[test]
+    fn test_from_dom_pairs() {
+        use crate::error::Error;
+        use chrono::{Local, TimeZone};
+
+        fn pairs(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        }
+
+        let task = Task::<TW26>::from_dom_pairs(&pairs(&[
+            ("description", "Buy milk"),
+            ("uuid", "8ca953d5-18b4-4eb9-bd56-18f2e5b752f0"),
+            ("status", "Pending"),
+            ("due", "2015-06-20T12:00:00"),
+            ("entry", "20150619T165438Z"),
+            ("tags", "shopping,home"),
+            ("annotation_1434733200", "whole milk"),
+            ("estimate", "30"),
+        ]))
+        .unwrap();
+
+        assert_eq!(task.description(), "Buy milk");
+        assert_eq!(task.uuid(), &uuid!("8ca953d5-18b4-4eb9-bd56-18f2e5b752f0"));
+        assert_eq!(task.status(), &TaskStatus::Pending);
+        let due = Local
+            .from_local_datetime(&mkdate("20150620T120000Z"))
+            .unwrap()
+            .naive_utc();
+        assert_eq!(task.due(), Some(&Date::from(due)));
+        assert_eq!(task.entry(), &mkdate("20150619T165438Z"));
+        assert_eq!(task.tags(), Some(&tags(&["shopping", "home"])));
+        assert_eq!(
+            task.annotations(),
+            Some(&vec![Annotation::new(
+                mkdate("20150619T170000Z"),
+                "whole milk".to_owned()
+            )])
+        );
+        assert_eq!(
+            task.uda().get("estimate"),
+            Some(&UDAValue::Str("30".to_owned()))
+        );
+
+        assert!(matches!(
+            Task::<TW26>::from_dom_pairs(&pairs(&[("description", "x"), ("due", "soon")])),
+            Err(Error::InvalidFieldValue(ref name)) if name == "due"
+        ));
+        assert!(matches!(
+            Task::<TW26>::from_dom_pairs(&pairs(&[("project", "home")])),
+            Err(Error::InvalidFieldValue(ref name)) if name == "description"
+        ));
+    }
+
+    #[test]
+    fn test_add_clear_tags() {
+        let mut task = valid_task();
+        task.add_tags(Vec::<Tag>::new());
+        assert_eq!(task.tags(), None);
+
+        task.add_tags(tags(&["a", "b"]));
+        task.add_tags(tags(&["b", "c"]));
+        assert_eq!(task.tags(), Some(&tags(&["a", "b", "c"])));
+
+        task.clear_tags();
+        assert_eq!(task.tags(), None);
+        assert!(!serde_json::to_string(&task).unwrap().contains("tags"));
+    }
+
+    #[test]
+    fn test_tags_contains() {
+        let mut task = valid_task();
+        assert!(!task.has_tag("a"));
+        assert!(!task.tags_contains_any(&["a"]));
+        assert!(task.tags_contains_all(&[]));
+
+        task.set_tags(Some(tags(&["a", "b"])));
+        assert!(task.has_tag("a"));
+        assert!(task.tags_contains_any(&["a", "c"]));
+        assert!(!task.tags_contains_any(&["c", "d"]));
+        assert!(!task.tags_contains_any(&[]));
+        assert!(task.tags_contains_all(&["b", "a"]));
+        assert!(!task.tags_contains_all(&["a", "c"]));
+        assert!(task.tags_contains_all(&[]));
+    }
+
+    #[test]
+    fn test_iter_collections() {
+        let task = valid_task();
+        assert_eq!(task.iter_tags().count(), 0);
+        assert_eq!(task.iter_annotations().count(), 0);
+        assert_eq!(task.iter_depends().count(), 0);
+
+        let s = r#"{"description":"d","entry":"20150619T165438Z","status":"pending","uuid":"8ca953d5-18b4-4eb9-bd56-18f2e5b752f0","tags":["a","b"],"depends":["5a04bb1e-3f4b-49fb-b9ba-44407ca223b5"]}"#;
+        let task: Task = serde_json::from_str(s).unwrap();
+        let names: Vec<&str> = task.iter_tags().map(Tag::as_str).collect();
+        assert_eq!(names, ["a", "b"]);
+        assert_eq!(task.iter_depends().count(), 1);
+        assert_eq!(task.iter_annotations().count(), 0);
+    }
+
+    #[test]
+    fn test_age_time_to_due() {
+        use chrono::Duration;
+
+        let mut task = valid_task();
+        let now = mkdate("20150620T165438Z");
+        assert_eq!(task.age_at(&now), Duration::days(1));
+        assert_eq!(task.time_to_due_at(&now), None);
+
+        task.set_due(Some(mkdate("20150620T180000Z")));
+        assert_eq!(
+            task.time_to_due_at(&now),
+            Some(Duration::hours(1) + Duration::seconds(322))
+        );
+        assert_eq!(
+            task.time_to_due_at(&mkdate("20150621T180000Z")),
+            Some(Duration::days(-1))
+        );
+    }
+
+    #[test]
+    fn test_builder_with_tag() {
+        use crate::task::TaskBuilder;
+
+        let task = TaskBuilder::<TW26>::default()
+            .description("test")
+            .with_tag(Tag::new("a").unwrap())
+            .with_tag(Tag::new("b").unwrap())
+            .with_annotation(Annotation::new(
+                mkdate("20150619T165438Z"),
+                "note".to_owned(),
+            ))
+            .build()
+            .unwrap();
+        assert_eq!(task.tags().unwrap(), &vec!["a".to_owned(), "b".to_owned()]);
+        assert_eq!(task.annotations().unwrap().len(), 1);
+
+        let task = TaskBuilder::<TW26>::default()
+            .description("test")
+            .tags(tags(&["a"]))
+            .with_tag(Tag::new("b").unwrap())
+            .build()
+            .unwrap();
+        assert_eq!(task.tags().unwrap(), &vec!["a".to_owned(), "b".to_owned()]);
+    }
+
+    #[test]
+    fn test_user_tags() {
+        let mut task = valid_task();
+        assert_eq!(task.user_tags().count(), 0);
+
+        task.set_tags(Some(tags(&["work", "OVERDUE", "home"])));
+        let user_tags: Vec<&Tag> = task.user_tags().collect();
+        assert_eq!(user_tags, ["work", "home"]);
+        assert_eq!(task.tags().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_add_annotation() {
+        let mut task = valid_task();
+        for (entry, description) in [
+            ("20150621T000000Z", "third"),
+            ("20150619T000000Z", "first"),
+            ("20150620T000000Z", "second"),
+            ("20150620T000000Z", "second, too"),
+        ] {
+            task.add_annotation(Annotation::new(mkdate(entry), description.to_owned()));
+        }
+
+        let descriptions: Vec<&str> = task
+            .annotations()
+            .unwrap()
+            .iter()
+            .map(|a| a.description().as_str())
+            .collect();
+        assert_eq!(descriptions, ["first", "second", "second, too", "third"]);
+    }
+
+    #[test]
+    fn test_repair_sets_end() {
+        let mut task = valid_task();
+        *task.status_mut() = TaskStatus::Completed;
+        task.set_modified(Some(mkdate("20160327T164007Z")));
+        task.set_start(Some(mkdate("20160327T100000Z")));
+
+        assert_eq!(
+            task.repair(),
+            [
+                Repair::EndSet(mkdate("20160327T164007Z")),
+                Repair::StartCleared(mkdate("20160327T100000Z")),
+            ]
+        );
+        assert_eq!(task.end(), Some(&mkdate("20160327T164007Z")));
+        assert!(task.start().is_none());
+        assert!(task.validate().is_ok());
+        assert!(task.repair().is_empty());
+    }
+
+    #[test]
+    fn test_repair_sets_end_without_modified() {
+        let mut task = valid_task();
+        *task.status_mut() = TaskStatus::Deleted;
+        task.set_modified(None::<Date>);
+
+        let repairs = task.repair();
+        assert_eq!(repairs.len(), 1);
+        assert!(matches!(repairs[0], Repair::EndSet(ref end) if Some(end) == task.end()));
+    }
+
+    #[test]
+    fn test_repair_clears_end() {
+        let mut task = valid_task();
+        task.set_end(Some(mkdate("20160327T164007Z")));
+
+        assert_eq!(
+            task.repair(),
+            [Repair::EndCleared(mkdate("20160327T164007Z"))]
+        );
+        assert!(task.end().is_none());
+        assert_eq!(*task.status(), TaskStatus::Pending);
+    }
+
+    #[test]
+    fn test_estimate() {
+        let s = r#"{"description":"d","entry":"20150619T165438Z","status":"pending","uuid":"8ca953d5-18b4-4eb9-bd56-18f2e5b752f0","estimate":"30min"}"#;
+        let task: Task = serde_json::from_str(s).unwrap();
+
+        assert_eq!(
+            task.estimate().map(chrono::Duration::from),
+            Some(chrono::Duration::minutes(30))
+        );
+        assert_eq!(
+            task.uda().get("estimate"),
+            Some(&UDAValue::Str("30min".to_owned()))
+        );
+
+        let mut task = valid_task();
+        assert!(task.estimate().is_none());
+        task.uda_mut()
+            .insert("estimate".to_owned(), UDAValue::Str("soon".to_owned()));
+        assert!(task.estimate().is_none());
+    }
+
+    #[test]
+    fn test_annotations_since() {
+        let mut task = valid_task();
+        task.set_annotations(Some([
+            (mkdate("20150621T000000Z"), "third"),
+            (mkdate("20150619T000000Z"), "first"),
+            (mkdate("20150620T000000Z"), "second"),
+        ]));
+
+        let descriptions: Vec<&str> = task
+            .annotations_since(&mkdate("20150620T000000Z"))
+            .into_iter()
+            .map(|a| a.description().as_str())
+            .collect();
+        assert_eq!(descriptions, ["second", "third"]);
+        assert!(task
+            .annotations_since(&mkdate("20150622T000000Z"))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_set_annotations_from_tuples() {
+        let mut task = valid_task();
+        task.set_annotations(Some(vec![
+            (mkdate("20150619T165438Z"), "first".to_owned()),
+            (mkdate("20150620T165438Z"), "second".to_owned()),
+        ]));
+        let annotations = task.annotations().unwrap();
+        assert_eq!(annotations.len(), 2);
+        assert_eq!(annotations[0].entry(), &mkdate("20150619T165438Z"));
+        assert_eq!(annotations[1].description(), "second");
+
+        task.set_annotations(Some([(mkdate("20150621T165438Z"), "third")]));
+        assert_eq!(task.annotations().unwrap()[0].description(), "third");
+    }
+
+    #[test]
+    fn test_status_predicates() {
+        let predicates = |task: &Task<TW26>| {
+            [
+                task.is_pending(),
+                task.is_waiting(),
+                task.is_completed(),
+                task.is_deleted(),
+                task.is_recurring(),
+                task.is_active(),
+            ]
+        };
+
+        let mut task = valid_task();
+        assert_eq!(predicates(&task), [true, false, false, false, false, false]);
+
+        task.start_now();
+        assert_eq!(predicates(&task), [true, false, false, false, false, true]);
+
+        *task.status_mut() = TaskStatus::Waiting;
+        assert_eq!(predicates(&task), [false, true, false, false, false, false]);
+
+        *task.status_mut() = TaskStatus::Recurring;
+        assert_eq!(predicates(&task), [false, false, false, false, true, false]);
+
+        task.complete();
+        assert_eq!(predicates(&task), [false, false, true, false, false, false]);
+
+        task.delete();
+        assert_eq!(predicates(&task), [false, false, false, true, false, false]);
+    }
+
+    /// Property tests, serializing arbitrary tasks and importing them again
+    ///
+    /// All fields are generated, including the volatile ones, so the reimported task has to be
+    /// equal in every field. Some values are left out because they can not be represented:
+    ///
+    /// * UDA names are prefixed with `uda_`, a UDA named like a standard field would be imported
+    ///   as that field.
+    /// * Floats (`urgency` and `F64` UDAs) are finite, JSON has no representation for NaN and
+    ///   infinity.
+    /// * With the `integral-floats` feature, `F64` UDAs are never whole-valued, as those are
+    ///   exported as integers and imported as `U64`.
+    /// * Dates have no subseconds, taskwarrior dates have a precision of one second.
+    /// * On `TW25`, `depends` is never an empty list, because that is exported as empty string,
+    ///   which is imported as `None`.
+    mod roundtrip {
+        use std::fmt::Debug;
+
+        use proptest::collection::{btree_map, vec};
+        use proptest::option::of;
+        use proptest::prelude::*;
+        use uuid::Uuid;
+
+        use crate::annotation::Annotation;
+        use crate::date::Date;
+        use crate::export::export;
+        use crate::import::import;
+        use crate::mask::{Mask, MaskState};
+        use crate::project::Project;
+        use crate::status::TaskStatus;
+        use crate::tag::Tag;
+        use crate::task::{Task, TaskWarriorVersion, TW25, TW26};
+        use crate::uda::{UDAValue, UDA};
+        use crate::urgency::Urgency;
+
+        fn date() -> impl Strategy<Value = Date> {
+            // Between 1970 and 2100
+            (0i64..4_102_444_800).prop_map(|secs| Date::from_timestamp(secs).unwrap())
+        }
+
+        fn uuid() -> impl Strategy<Value = Uuid> {
+            any::<u128>().prop_map(Uuid::from_u128)
+        }
+
+        fn status() -> impl Strategy<Value = TaskStatus> {
+            prop_oneof![
+                Just(TaskStatus::Pending),
+                Just(TaskStatus::Deleted),
+                Just(TaskStatus::Completed),
+                Just(TaskStatus::Waiting),
+                Just(TaskStatus::Recurring),
+            ]
+        }
+
+        fn mask() -> impl Strategy<Value = Mask> {
+            let state = prop_oneof![
+                Just(MaskState::Pending),
+                Just(MaskState::Completed),
+                Just(MaskState::Deleted),
+                Just(MaskState::Waiting),
+            ];
+            vec(state, 0..8).prop_map(Mask::from)
+        }
+
+        fn uda() -> impl Strategy<Value = UDA> {
+            let value = prop_oneof![
+                any::<String>().prop_map(UDAValue::Str),
+                any::<u64>().prop_map(UDAValue::U64),
+                (-1e9f64..1e9)
+                    // Positive whole-valued floats are imported as U64 with `integral-floats`
+                    .prop_filter("positive whole-valued", |f| {
+                        !cfg!(feature = "integral-floats") || f.fract() != 0.0 || *f < 0.0
+                    })
+                    .prop_map(UDAValue::F64),
+            ];
+            btree_map("uda_[a-z]{1,8}", value, 0..4)
+        }
+
+        fn task<V: TaskWarriorVersion + Debug + 'static>(
+            min_depends: usize,
+        ) -> impl Strategy<Value = Task<V>> {
+            let required = (of(any::<u64>()), status(), uuid(), date(), any::<String>());
+            let lists = (
+                of(vec((date(), any::<String>()), 0..3)),
+                of(vec(uuid(), min_depends..3)),
+                of(vec("[a-zA-Z0-9_.]{1,10}", 0..3)),
+            );
+            let dates = (
+                of(date()),
+                of(date()),
+                of(date()),
+                of(date()),
+                of(date()),
+                of(date()),
+                of(date()),
+            );
+            let other = (
+                of(any::<u64>()),
+                of(mask()),
+                of(uuid()),
+                of("[HML]"),
+                of(any::<String>().prop_filter_map("empty project", |p| Project::new(p).ok())),
+                of("[a-z]{1,10}"),
+                of(-100f64..100.0),
+                uda(),
+            );
+
+            (required, lists, dates, other).prop_map(
+                |(
+                    (id, status, uuid, entry, description),
+                    (annotations, depends, tags),
+                    (due, end, modified, scheduled, start, until, wait),
+                    (imask, mask, parent, priority, project, recur, urgency, uda),
+                )| {
+                    Task::new(
+                        id,
+                        status,
+                        uuid,
+                        entry,
+                        description,
+                        annotations.map(|a| a.into_iter().map(Annotation::from).collect()),
+                        depends,
+                        due,
+                        end,
+                        imask,
+                        mask,
+                        modified,
+                        parent,
+                        priority,
+                        project,
+                        recur,
+                        scheduled,
+                        start,
+                        tags.map(|t| t.into_iter().map(|t| Tag::new(t).unwrap()).collect()),
+                        until,
+                        wait,
+                        urgency.map(|u| Urgency::new(u).unwrap()),
+                        uda,
+                    )
+                },
+            )
+        }
+
+        fn reimport<V: TaskWarriorVersion>(task: &Task<V>) -> Task<V> {
+            let mut buf = Vec::new();
+            export(std::slice::from_ref(task), &mut buf).unwrap();
+            import::<V, _>(&buf[..]).unwrap().remove(0)
+        }
+
+        proptest! {
+            #[test]
+            fn test_roundtrip_tw26(task in task::<TW26>(0)) {
+                prop_assert_eq!(reimport(&task), task);
+            }
+
+            #[test]
+            fn test_roundtrip_tw25(task in task::<TW25>(1)) {
+                prop_assert_eq!(reimport(&task), task);
+            }
+        }
+    }
+
+    /// Golden tests, serializing a task with every field set and comparing the output with the
+    /// checked-in JSON
+    ///
+    /// Any change of the serialization format makes these tests fail. If the change is
+    /// intended, update the golden JSON. Floats are not whole-valued, so the output does not
+    /// depend on the `integral-floats` feature.
+    mod golden {
+        use uuid::uuid;
+
+        use crate::annotation::Annotation;
+        use crate::date::Date;
+        use crate::mask::{Mask, MaskState};
+        use crate::project::Project;
+        use crate::status::TaskStatus;
+        use crate::tag::Tag;
+        use crate::task::{Task, TaskWarriorVersion, TW25, TW26};
+        use crate::uda::{UDAValue, UDA};
+        use crate::urgency::Urgency;
+
+        const GOLDEN_TW26: &str = r#"{
+  "id": 12,
+  "status": "completed",
+  "uuid": "8ca953d5-18b4-4eb9-bd56-18f2e5b752f0",
+  "entry": "20150619T165438Z",
+  "description": "Buy \"milk\", eggs",
+  "annotations": [
+    {
+      "entry": "20150619T175438Z",
+      "description": "first"
+    },
+    {
+      "entry": "20150619T185438Z",
+      "description": "second"
+    }
+  ],
+  "depends": [
+    "54d49ffc-a06b-4dd8-b7d1-db5f50594312",
+    "08ee8dce-cb97-4c8c-9940-c9a440e90119"
+  ],
+  "due": "20150622T190640Z",
+  "end": "20150623T225320Z",
+  "imask": 2,
+  "mask": "+X-",
+  "modified": "20150623T225320Z",
+  "parent": "1f2e3d4c-5b6a-4978-8695-a4b3c2d1e0f9",
+  "priority": "H",
+  "project": "home.garden",
+  "recur": "weekly",
+  "scheduled": "20150621T152000Z",
+  "start": "20150622T051320Z",
+  "tags": [
+    "home",
+    "errand"
+  ],
+  "until": "20150819T160000Z",
+  "wait": "20150620T113320Z",
+  "urgency": 4.25,
+  "estimate": "30min",
+  "points": 3,
+  "weight": 0.75
+}"#;
+
+        const GOLDEN_TW25: &str = r#"{
+  "id": 12,
+  "status": "completed",
+  "uuid": "8ca953d5-18b4-4eb9-bd56-18f2e5b752f0",
+  "entry": "20150619T165438Z",
+  "description": "Buy \"milk\", eggs",
+  "annotations": [
+    {
+      "entry": "20150619T175438Z",
+      "description": "first"
+    },
+    {
+      "entry": "20150619T185438Z",
+      "description": "second"
+    }
+  ],
+  "depends": "54d49ffc-a06b-4dd8-b7d1-db5f50594312,08ee8dce-cb97-4c8c-9940-c9a440e90119",
+  "due": "20150622T190640Z",
+  "end": "20150623T225320Z",
+  "imask": 2,
+  "mask": "+X-",
+  "modified": "20150623T225320Z",
+  "parent": "1f2e3d4c-5b6a-4978-8695-a4b3c2d1e0f9",
+  "priority": "H",
+  "project": "home.garden",
+  "recur": "weekly",
+  "scheduled": "20150621T152000Z",
+  "start": "20150622T051320Z",
+  "tags": [
+    "home",
+    "errand"
+  ],
+  "until": "20150819T160000Z",
+  "wait": "20150620T113320Z",
+  "urgency": 4.25,
+  "estimate": "30min",
+  "points": 3,
+  "weight": 0.75
+}"#;
+
+        fn date(secs: i64) -> Date {
+            Date::from_timestamp(secs).unwrap()
+        }
+
+        fn full_task<V: TaskWarriorVersion>() -> Task<V> {
+            let mut uda = UDA::new();
+            uda.insert("estimate".to_owned(), UDAValue::Str("30min".to_owned()));
+            uda.insert("points".to_owned(), UDAValue::U64(3));
+            uda.insert("weight".to_owned(), UDAValue::F64(0.75));
+
+            Task::new(
+                Some(12),
+                TaskStatus::Completed,
+                uuid!("8ca953d5-18b4-4eb9-bd56-18f2e5b752f0"),
+                date(1_434_732_878),
+                "Buy \"milk\", eggs".to_owned(),
+                Some(vec![
+                    Annotation::new(date(1_434_736_478), "first".to_owned()),
+                    Annotation::new(date(1_434_740_078), "second".to_owned()),
+                ]),
+                Some(vec![
+                    uuid!("54d49ffc-a06b-4dd8-b7d1-db5f50594312"),
+                    uuid!("08ee8dce-cb97-4c8c-9940-c9a440e90119"),
+                ]),
+                Some(date(1_435_000_000)),
+                Some(date(1_435_100_000)),
+                Some(2),
+                Some(Mask::from(vec![
+                    MaskState::Completed,
+                    MaskState::Deleted,
+                    MaskState::Pending,
+                ])),
+                Some(date(1_435_100_000)),
+                Some(uuid!("1f2e3d4c-5b6a-4978-8695-a4b3c2d1e0f9")),
+                Some("H".to_owned()),
+                Some(Project::new("home.garden").unwrap()),
+                Some("weekly".to_owned()),
+                Some(date(1_434_900_000)),
+                Some(date(1_434_950_000)),
+                Some(vec![Tag::new("home").unwrap(), Tag::new("errand").unwrap()]),
+                Some(date(1_440_000_000)),
+                Some(date(1_434_800_000)),
+                Some(Urgency::from(4.25)),
+                uda,
+            )
+        }
+
+        #[test]
+        fn test_golden_tw26() {
+            let json = serde_json::to_string_pretty(&full_task::<TW26>()).unwrap();
+            assert_eq!(json, GOLDEN_TW26);
+        }
+
+        #[test]
+        fn test_golden_tw25() {
+            let json = serde_json::to_string_pretty(&full_task::<TW25>()).unwrap();
+            assert_eq!(json, GOLDEN_TW25);
+        }
+    }
 }
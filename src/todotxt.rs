@@ -0,0 +1,190 @@
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+
+//! Module for converting tasks from and to the todo.txt format
+//!
+//! The fields are mapped as follows:
+//!
+//! - The `x` completion marker maps to the `Completed` status, the completion date to `end`
+//! - The priorities `(A)`, `(B)` and `(C)` map to `H`, `M` and `L`, other priorities are dropped
+//! - The creation date maps to `entry`
+//! - The first `+project` maps to the project
+//! - `@context`s map to tags
+//! - `due:YYYY-MM-DD` maps to the due date
+//!
+//! Everything else is kept in the description.
+
+use chrono::{NaiveDate, NaiveTime};
+
+use crate::date::Date;
+use crate::project::Project;
+use crate::status::TaskStatus;
+use crate::tag::Tag;
+use crate::task::{Task, TaskBuilder, TaskWarriorVersion};
+
+/// The date template used by todo.txt
+pub static TODOTXT_DATE_TEMPLATE: &str = "%Y-%m-%d";
+
+/// Create a task from a line in todo.txt format
+pub fn from_todotxt_line<V: TaskWarriorVersion + Clone>(line: &str) -> Task<V> {
+    let mut words = line.split_whitespace().peekable();
+    let mut builder = TaskBuilder::<V>::default();
+
+    if words.peek() == Some(&"x") {
+        words.next();
+        builder.status(TaskStatus::Completed);
+        if let Some(end) = words.peek().and_then(|w| parse_date(w)) {
+            words.next();
+            builder.end(end);
+        }
+    } else if let Some(priority) = words.peek().and_then(|w| parse_priority(w)) {
+        words.next();
+        builder.priority(priority.to_owned());
+    }
+    if let Some(entry) = words.peek().and_then(|w| parse_date(w)) {
+        words.next();
+        builder.entry(entry);
+    }
+
+    let mut description = Vec::new();
+    let mut project = None;
+    let mut tags = Vec::new();
+    for word in words {
+        if let Some(p) = word.strip_prefix('+').and_then(|p| Project::new(p).ok()) {
+            if project.is_none() {
+                project = Some(p);
+                continue;
+            }
+        } else if let Some(context) = word.strip_prefix('@').and_then(|c| Tag::new(c).ok()) {
+            tags.push(context);
+            continue;
+        } else if let Some(due) = word.strip_prefix("due:").and_then(parse_date) {
+            builder.due(due);
+            continue;
+        } else if let Some(priority) = word
+            .strip_prefix("pri:")
+            .and_then(|p| parse_priority(&format!("({})", p)))
+        {
+            builder.priority(priority.to_owned());
+            continue;
+        }
+        description.push(word);
+    }
+
+    builder.description(description.join(" "));
+    if let Some(project) = project {
+        builder.project(project);
+    }
+    if !tags.is_empty() {
+        builder.tags(tags);
+    }
+    // The description is the only field without a default, and it is always set
+    builder.build().unwrap()
+}
+
+/// Render a task as a line in todo.txt format
+pub fn to_todotxt_line<V: TaskWarriorVersion>(task: &Task<V>) -> String {
+    let mut words = Vec::new();
+    let completed = *task.status() == TaskStatus::Completed;
+    let priority = task.priority().and_then(|p| match p.as_str() {
+        "H" => Some("A"),
+        "M" => Some("B"),
+        "L" => Some("C"),
+        _ => None,
+    });
+
+    if completed {
+        words.push("x".to_owned());
+        if let Some(end) = task.end() {
+            words.push(format_date(end));
+        }
+    } else if let Some(priority) = priority {
+        words.push(format!("({})", priority));
+    }
+    words.push(format_date(task.entry()));
+    words.push(task.description().clone());
+    if let Some(project) = task.project() {
+        words.push(format!("+{}", project));
+    }
+    for tag in task.tags().into_iter().flatten() {
+        words.push(format!("@{}", tag));
+    }
+    if let Some(due) = task.due() {
+        words.push(format!("due:{}", format_date(due)));
+    }
+    if completed {
+        // Completed tasks keep their priority as key:value tag
+        if let Some(priority) = priority {
+            words.push(format!("pri:{}", priority));
+        }
+    }
+
+    words.join(" ")
+}
+
+fn parse_date(s: &str) -> Option<Date> {
+    NaiveDate::parse_from_str(s, TODOTXT_DATE_TEMPLATE)
+        .ok()
+        .map(|d| Date::from(d.and_time(NaiveTime::MIN)))
+}
+
+fn format_date(date: &Date) -> String {
+    date.format(TODOTXT_DATE_TEMPLATE).to_string()
+}
+
+fn parse_priority(s: &str) -> Option<&'static str> {
+    match s {
+        "(A)" => Some("H"),
+        "(B)" => Some("M"),
+        "(C)" => Some("L"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::project::Project;
+    use crate::status::TaskStatus;
+    use crate::task::{Task, TW26};
+    use crate::todotxt::{from_todotxt_line, to_todotxt_line};
+
+    const LINE: &str = "(A) 2024-01-02 Call mom +family @phone @home due:2024-01-10";
+
+    #[test]
+    fn test_from_todotxt_line() {
+        let task: Task<TW26> = from_todotxt_line(LINE);
+
+        assert_eq!(*task.status(), TaskStatus::Pending);
+        assert_eq!(task.description(), "Call mom");
+        assert_eq!(task.priority().map(String::as_str), Some("H"));
+        assert_eq!(task.project().map(Project::as_str), Some("family"));
+        assert_eq!(
+            task.tags().unwrap(),
+            &vec!["phone".to_owned(), "home".to_owned()]
+        );
+        assert_eq!(task.entry().format("%F").to_string(), "2024-01-02");
+        assert_eq!(task.due().unwrap().format("%F").to_string(), "2024-01-10");
+    }
+
+    #[test]
+    fn test_todotxt_roundtrip() {
+        let task: Task<TW26> = from_todotxt_line(LINE);
+        assert_eq!(to_todotxt_line(&task), LINE);
+    }
+
+    #[test]
+    fn test_todotxt_completed() {
+        let line = "x 2024-01-03 2024-01-02 Call mom +family pri:B";
+        let task: Task<TW26> = from_todotxt_line(line);
+
+        assert_eq!(*task.status(), TaskStatus::Completed);
+        assert_eq!(task.end().unwrap().format("%F").to_string(), "2024-01-03");
+        assert_eq!(task.entry().format("%F").to_string(), "2024-01-02");
+        assert_eq!(task.priority().map(String::as_str), Some("M"));
+        assert_eq!(task.description(), "Call mom");
+        assert_eq!(to_todotxt_line(&task), line);
+    }
+}
@@ -11,22 +11,337 @@
 use crate::error::Error;
 use crate::import::import;
 use crate::task::Task;
-use std::io::Write;
+use std::ffi::{OsStr, OsString};
+use std::fmt;
+use std::io::{BufWriter, Write};
 use std::iter::once;
-use std::process::{Child, Command, Stdio};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Output, Stdio};
+use std::str::FromStr;
+use std::thread::{self, JoinHandle};
 
+use uuid::Uuid;
+
+use serde::Serialize;
 use serde_json;
 
+/// The version of a taskwarrior installation, as reported by `task --version`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TaskVersion {
+    /// The major version
+    pub major: u32,
+    /// The minor version
+    pub minor: u32,
+    /// The patch version
+    pub patch: u32,
+}
+
+impl TaskVersion {
+    /// Whether this version exports `depends` as JSON array, i.e. whether [TW26] has to be used
+    /// instead of [TW25] to parse its tasks. This is the case for 2.6.0 and newer.
+    ///
+    /// [TW26]: crate::task::TW26
+    /// [TW25]: crate::task::TW25
+    pub fn has_depends_array(&self) -> bool {
+        (self.major, self.minor) >= (2, 6)
+    }
+}
+
+impl FromStr for TaskVersion {
+    type Err = Error;
+
+    /// Parse the output of `task --version`, like `2.6.2` or `3.0.0-beta`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::UnexpectedOutput(s.to_owned());
+        let version = s.split_whitespace().next().ok_or_else(invalid)?;
+        let mut parts = version.trim_start_matches('v').split('.').map(|part| {
+            let digits = part
+                .find(|c: char| !c.is_ascii_digit())
+                .map_or(part, |end| &part[..end]);
+            digits.parse::<u32>().map_err(|_| invalid())
+        });
+
+        Ok(TaskVersion {
+            major: parts.next().ok_or_else(invalid)??,
+            minor: parts.next().ok_or_else(invalid)??,
+            patch: parts.next().transpose()?.unwrap_or(0),
+        })
+    }
+}
+
+impl fmt::Display for TaskVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Handle to a taskwarrior installation, i.e. the `task` binary which should be called.
+///
+/// The free functions in this module use the default, which is the `task` binary from your path
+/// with the environment inherited from the current process.
+#[derive(Debug, Clone)]
+pub struct TaskWarrior {
+    binary: PathBuf,
+    env: Vec<(OsString, OsString)>,
+}
+
+impl Default for TaskWarrior {
+    fn default() -> Self {
+        TaskWarrior::new("task")
+    }
+}
+
+impl TaskWarrior {
+    /// Create a new TaskWarrior handle which calls the given binary
+    pub fn new<P: Into<PathBuf>>(binary: P) -> Self {
+        TaskWarrior {
+            binary: binary.into(),
+            env: Vec::new(),
+        }
+    }
+
+    /// Set an environment variable for all spawned commands
+    pub fn env<K, V>(mut self, key: K, value: V) -> Self
+    where
+        K: AsRef<OsStr>,
+        V: AsRef<OsStr>,
+    {
+        self.env
+            .push((key.as_ref().to_owned(), value.as_ref().to_owned()));
+        self
+    }
+
+    /// Use the given taskrc file, by setting `TASKRC` for all spawned commands
+    pub fn taskrc<P: AsRef<Path>>(self, path: P) -> Self {
+        self.env("TASKRC", path.as_ref())
+    }
+
+    /// Use the given data directory, by setting `TASKDATA` for all spawned commands
+    pub fn taskdata<P: AsRef<Path>>(self, path: P) -> Self {
+        self.env("TASKDATA", path.as_ref())
+    }
+
+    /// Get the path of the binary which is called
+    pub fn binary(&self) -> &Path {
+        &self.binary
+    }
+
+    /// Get a Command for the binary with the configured environment, which can be extended with
+    /// arguments
+    pub fn command(&self) -> Command {
+        let mut cmd = Command::new(&self.binary);
+        cmd.envs(self.env.iter().map(|(k, v)| (k, v)));
+        cmd
+    }
+
+    /// This will give you the version of taskwarrior, by running `task --version`.
+    pub fn version(&self) -> Result<TaskVersion, Error> {
+        let mut cmd = self.command();
+        cmd.arg("--version").stderr(Stdio::piped());
+        let output = check_output(cmd.output()?)?;
+        String::from_utf8_lossy(&output.stdout).parse()
+    }
+
+    /// This will give you all tasks which match the given query in the taskwarrior query syntax.
+    /// This is not sanitized. Never get the query string from an untrusted user.
+    pub fn query(&self, query: &str) -> Result<Vec<Task>, Error> {
+        let mut cmd = add_query_to_cmd(query, self.command());
+        cmd.stdout(Stdio::piped());
+        run_query_cmd(cmd)
+    }
+
+    /// This will give you all tasks which match the query built with the `QueryBuilder`.
+    pub fn query_builder(&self, query: &QueryBuilder) -> Result<Vec<Task>, Error> {
+        let mut cmd = query.add_to_cmd(self.command());
+        cmd.stdout(Stdio::piped());
+        run_query_cmd(cmd)
+    }
+
+    /// This will give you the task with the given uuid, or `None` if there is no such task.
+    pub fn get_task(&self, uuid: &Uuid) -> Result<Option<Task>, Error> {
+        let mut tasks = self.query(&uuid.to_string())?;
+        match tasks.len() {
+            0 | 1 => Ok(tasks.pop()),
+            n => Err(Error::NotUnique(n)),
+        }
+    }
+
+    /// This will give you the number of tasks which match the given query, without exporting them.
+    /// This is not sanitized. Never get the query string from an untrusted user.
+    pub fn count(&self, query: &str) -> Result<usize, Error> {
+        let mut cmd = self.command();
+        cmd.args(query.split_whitespace()).arg("count");
+        let output = check_output(cmd.stderr(Stdio::piped()).output()?)?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        stdout
+            .trim()
+            .parse()
+            .map_err(|_| Error::UnexpectedOutput(stdout.into_owned()))
+    }
+
+    /// This will create a new task by running `task add <args...> -- <description>` and return
+    /// its uuid, which is looked up with `task +LATEST export` afterwards.
+    /// Every argument is passed as a single argument, the description is taken literally.
+    /// This is not sanitized. Never get the arguments from an untrusted user.
+    pub fn add(&self, description: &str, args: &[&str]) -> Result<Uuid, Error> {
+        let mut cmd = self.command();
+        cmd.arg("add")
+            .args(args)
+            .arg("--")
+            .arg(description)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped());
+        check_output(cmd.output()?)?;
+
+        let tasks = self.query("+LATEST")?;
+        match tasks.as_slice() {
+            [task] => Ok(*task.uuid()),
+            tasks => Err(Error::NotUnique(tasks.len())),
+        }
+    }
+
+    /// This will apply the modifications to all tasks which match the given query, by running
+    /// `task <query> modify <modifications...>`.
+    /// The query is split at whitespace, while every modification is passed as a single argument,
+    /// so a modification like `description:two words` needs no quoting.
+    /// This is not sanitized. Never get the query or the modifications from an untrusted user.
+    ///
+    /// The stdin of the `task` binary is closed, so it does not wait for a confirmation if many
+    /// tasks match. Whether it asks at all depends on the `bulk` setting of your taskrc.
+    pub fn modify(&self, query: &str, modifications: &[&str]) -> Result<(), Error> {
+        self.run_on_query(&[], query, "modify", modifications)
+    }
+
+    /// This will mark all tasks which match the given query as done, by running
+    /// `task <query> done`.
+    /// This is not sanitized. Never get the query string from an untrusted user.
+    pub fn done(&self, query: &str) -> Result<(), Error> {
+        self.run_on_query(&[], query, "done", &[])
+    }
+
+    /// This will delete all tasks which match the given query, by running
+    /// `task rc.confirmation=off <query> delete`, so taskwarrior does not ask for a confirmation.
+    /// This is not sanitized. Never get the query string from an untrusted user.
+    pub fn delete(&self, query: &str) -> Result<(), Error> {
+        self.run_on_query(&["rc.confirmation=off"], query, "delete", &[])
+    }
+
+    /// This will save the given tasks to taskwarrior. Call with `Some(&task)` if you just have one
+    /// task.
+    /// This will block until the save was successful.
+    ///
+    /// The tasks are streamed to `task import` from a separate thread while its output is read,
+    /// so neither side blocks on a full pipe, even for large numbers of tasks.
+    pub fn save<'a, T>(&self, tasks: T) -> Result<(), Error>
+    where
+        T: IntoIterator<Item = &'a Task>,
+    {
+        let tasks: Vec<&Task> = tasks.into_iter().collect();
+        let mut import = self.import_command().stderr(Stdio::piped()).spawn()?;
+        let stdin = import.stdin.take().ok_or(Error::TaskCmdError)?;
+
+        let (written, output) = thread::scope(|s| {
+            let writer = s.spawn(|| write_tasks(tasks, stdin));
+            let output = import.wait_with_output();
+            (writer.join(), output)
+        });
+        check_output(output?)?;
+        written.unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    }
+
+    /// This function returns the handle to a child process which saves the given tasks.
+    ///
+    /// The tasks are moved to the thread which writes them, see `save_to_cmd()`.
+    pub fn save_async<T>(&self, tasks: T) -> Result<SaveHandle, Error>
+    where
+        T: IntoIterator<Item = Task>,
+        T::IntoIter: Send + 'static,
+    {
+        save_to_cmd(tasks, self.import_command())
+    }
+
+    /// Run `task <rc...> <query> <command> <args...>` and check its exit status
+    fn run_on_query(
+        &self,
+        rc: &[&str],
+        query: &str,
+        command: &str,
+        args: &[&str],
+    ) -> Result<(), Error> {
+        let mut cmd = self.command();
+        cmd.args(rc)
+            .args(query.split_whitespace())
+            .arg(command)
+            .args(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped());
+        check_output(cmd.output()?)?;
+        Ok(())
+    }
+
+    pub(crate) fn import_command(&self) -> Command {
+        let mut cmd = self.command();
+        cmd.arg("import").stdin(Stdio::piped());
+        cmd
+    }
+}
+
+/// This will give you the version of taskwarrior, so you can decide whether to use [TW25] or
+/// [TW26], see `TaskVersion::has_depends_array()`.
+///
+/// [TW26]: crate::task::TW26
+/// [TW25]: crate::task::TW25
+pub fn detect_version() -> Result<TaskVersion, Error> {
+    TaskWarrior::default().version()
+}
+
 /// This will give you all tasks which match the given query in the taskwarrior query syntax.
 /// This is not sanitized. Never get the query string from an untrusted user.
 pub fn query(query: &str) -> Result<Vec<Task>, Error> {
-    let mut cmd = add_query_to_cmd(query, Command::new("task"));
-    cmd.stdout(Stdio::piped());
-    run_query_cmd(cmd)
+    TaskWarrior::default().query(query)
+}
+
+/// This will give you the task with the given uuid, or `None` if there is no such task.
+pub fn get_task(uuid: &Uuid) -> Result<Option<Task>, Error> {
+    TaskWarrior::default().get_task(uuid)
+}
+
+/// This will give you the number of tasks which match the given query, without exporting them.
+/// This is not sanitized. Never get the query string from an untrusted user.
+pub fn count(query: &str) -> Result<usize, Error> {
+    TaskWarrior::default().count(query)
+}
+
+/// This will create a new task and return its uuid. See `TaskWarrior::add()` for details.
+/// This is not sanitized. Never get the arguments from an untrusted user.
+pub fn add(description: &str, args: &[&str]) -> Result<Uuid, Error> {
+    TaskWarrior::default().add(description, args)
+}
+
+/// This will apply the modifications to all tasks which match the given query.
+/// See `TaskWarrior::modify()` for details.
+/// This is not sanitized. Never get the query or the modifications from an untrusted user.
+pub fn modify(query: &str, modifications: &[&str]) -> Result<(), Error> {
+    TaskWarrior::default().modify(query, modifications)
+}
+
+/// This will mark all tasks which match the given query as done.
+/// This is not sanitized. Never get the query string from an untrusted user.
+pub fn done(query: &str) -> Result<(), Error> {
+    TaskWarrior::default().done(query)
+}
+
+/// This will delete all tasks which match the given query, without asking for a confirmation.
+/// This is not sanitized. Never get the query string from an untrusted user.
+pub fn delete(query: &str) -> Result<(), Error> {
+    TaskWarrior::default().delete(query)
 }
 
 /// This will take a Command, and append the given query string splited at whitespace followed by
 /// the "export" command to the arguments of the Command.
+/// Values containing whitespace are split as well, use the `QueryBuilder` for them.
 pub fn add_query_to_cmd(query: &str, mut cmd: Command) -> Command {
     for x in query.split_whitespace().chain(once("export")) {
         cmd.arg(x);
@@ -34,23 +349,161 @@ pub fn add_query_to_cmd(query: &str, mut cmd: Command) -> Command {
     cmd
 }
 
+/// Builds a taskwarrior query from structured parts, as alternative to `add_query_to_cmd()`
+///
+/// Every part becomes exactly one argument of the `task` binary, so values may contain
+/// whitespace or quotes, e.g. `attribute("description", "two words")`, and can never be taken
+/// as separate filter or command by taskwarrior. The names of attributes and modifiers are not
+/// checked, so they should not come from an untrusted user.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct QueryBuilder {
+    args: Vec<String>,
+}
+
+impl QueryBuilder {
+    /// Create an empty query, which matches all tasks
+    pub fn new() -> Self {
+        QueryBuilder::default()
+    }
+
+    /// Match tasks where the attribute has the value, like `project:home`
+    pub fn attribute(mut self, name: &str, value: &str) -> Self {
+        self.args.push(format!("{}:{}", name, value));
+        self
+    }
+
+    /// Match tasks where the attribute matches the value with the modifier, like
+    /// `description.contains:milk`
+    pub fn attribute_with(mut self, name: &str, modifier: &str, value: &str) -> Self {
+        self.args.push(format!("{}.{}:{}", name, modifier, value));
+        self
+    }
+
+    /// Match tasks which have the tag, like `+home`
+    pub fn tag(mut self, tag: &str) -> Self {
+        self.args.push(format!("+{}", tag));
+        self
+    }
+
+    /// Match tasks which do not have the tag, like `-home`
+    pub fn without_tag(mut self, tag: &str) -> Self {
+        self.args.push(format!("-{}", tag));
+        self
+    }
+
+    /// Match the task with the uuid
+    pub fn uuid(mut self, uuid: &Uuid) -> Self {
+        self.args.push(uuid.to_string());
+        self
+    }
+
+    /// Get the arguments of the query
+    pub fn args(&self) -> &[String] {
+        &self.args
+    }
+
+    /// Append the arguments of the query followed by the "export" command to the arguments of
+    /// the Command, like `add_query_to_cmd()`
+    pub fn add_to_cmd(&self, mut cmd: Command) -> Command {
+        cmd.args(&self.args).arg("export");
+        cmd
+    }
+}
+
 /// This executes the given Command and trys to convert the Result into a Vec<Task>.
+/// If the Command exits unsuccessfully, its stderr is returned in `Error::TaskCmdFailed`.
 pub fn run_query_cmd(mut cmd: Command) -> Result<Vec<Task>, Error> {
-    let mut export = cmd.spawn()?;
-    export.wait()?;
-    import(export.stdout.ok_or(Error::TaskCmdError)?)
+    let export = cmd.stderr(Stdio::piped()).spawn()?;
+    let output = check_output(export.wait_with_output()?)?;
+    import(&output.stdout[..])
+}
+
+/// Turn the Output of an unsuccessful Command into an `Error::TaskCmdFailed`
+pub(crate) fn check_output(output: Output) -> Result<Output, Error> {
+    if output.status.success() {
+        Ok(output)
+    } else {
+        Err(Error::TaskCmdFailed {
+            status: output.status,
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        })
+    }
+}
+
+/// A child process saving tasks, together with the thread writing the tasks to its stdin
+///
+/// Use `SaveHandle::wait()` to wait for both and check their results.
+#[derive(Debug)]
+pub struct SaveHandle {
+    child: Child,
+    writer: JoinHandle<Result<(), Error>>,
+}
+
+impl SaveHandle {
+    /// Get the child process
+    pub fn child(&self) -> &Child {
+        &self.child
+    }
+
+    /// Get the child process mutable, e.g. to read its stdout
+    pub fn child_mut(&mut self) -> &mut Child {
+        &mut self.child
+    }
+
+    /// Get the child process and the handle of the thread writing the tasks to its stdin
+    pub fn into_parts(self) -> (Child, JoinHandle<Result<(), Error>>) {
+        (self.child, self.writer)
+    }
+
+    /// Wait for the child process and the writer thread to finish
+    ///
+    /// Fails with `Error::TaskCmdFailed` if the child process exited unsuccessfully, and with the
+    /// error of the writer thread if the tasks could not be written completely, e.g. because the
+    /// child process closed its stdin early.
+    pub fn wait(self) -> Result<(), Error> {
+        let output = self.child.wait_with_output();
+        let written = self.writer.join();
+        check_output(output?)?;
+        written.unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    }
 }
 
 /// This function runs the given Command, pipes the tasks as JSON to it and returns a handle to the child process.
-pub fn save_to_cmd(tasks: Vec<&'_ Task>, mut cmd: Command) -> Result<Child, Error> {
-    let input_buffer = serde_json::to_string(&tasks)?;
-    let mut import = cmd.spawn()?;
-    import
-        .stdin
-        .as_mut()
-        .ok_or(Error::TaskCmdError)?
-        .write_all(input_buffer.as_bytes())?;
-    Ok(import)
+/// The stderr of the child process is piped as well, so it can be read on failure.
+///
+/// The tasks are moved to a separate thread, which serializes and writes them one after the
+/// other, so the output of the child process can be read while it is still reading its input.
+/// `SaveHandle::wait()` reports errors of both the child process and the writer thread.
+pub fn save_to_cmd<T>(tasks: T, mut cmd: Command) -> Result<SaveHandle, Error>
+where
+    T: IntoIterator<Item = Task>,
+    T::IntoIter: Send + 'static,
+{
+    let tasks = tasks.into_iter();
+    let mut child = cmd.stderr(Stdio::piped()).spawn()?;
+    let stdin = child.stdin.take().ok_or(Error::TaskCmdError)?;
+    let writer = thread::spawn(move || write_tasks(tasks, stdin));
+    Ok(SaveHandle { child, writer })
+}
+
+/// Write the tasks as JSON array to the stdin of a child process and close it afterwards
+///
+/// The tasks are serialized one after the other, so the whole JSON is never held in memory.
+fn write_tasks<T>(tasks: T, stdin: ChildStdin) -> Result<(), Error>
+where
+    T: IntoIterator,
+    T::Item: Serialize,
+{
+    let mut w = BufWriter::new(stdin);
+    w.write_all(b"[")?;
+    for (i, task) in tasks.into_iter().enumerate() {
+        if i > 0 {
+            w.write_all(b",")?;
+        }
+        serde_json::to_writer(&mut w, &task).map_err(Error::from_serialize)?;
+    }
+    w.write_all(b"]")?;
+    w.flush().map_err(Error::from)
 }
 
 /// This will save the given tasks to taskwarrior. Call with `Some(&task)` if you just have one
@@ -60,16 +513,368 @@ pub fn save<'a, T>(tasks: T) -> Result<(), Error>
 where
     T: IntoIterator<Item = &'a Task>,
 {
-    save_async(tasks)?.wait()?;
-    Ok(())
+    TaskWarrior::default().save(tasks)
 }
 
 /// This function returns the handle to a child process which saves the given tasks.
-pub fn save_async<'a, T>(tasks: T) -> Result<Child, Error>
+pub fn save_async<T>(tasks: T) -> Result<SaveHandle, Error>
 where
-    T: IntoIterator<Item = &'a Task>,
+    T: IntoIterator<Item = Task>,
+    T::IntoIter: Send + 'static,
 {
-    let mut cmd = Command::new("task");
-    cmd.arg("import").stdin(Stdio::piped());
-    save_to_cmd(tasks.into_iter().collect(), cmd)
+    TaskWarrior::default().save_async(tasks)
+}
+
+#[cfg(all(test, unix))]
+pub(crate) mod test {
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::PathBuf;
+    use std::sync::{Mutex, MutexGuard};
+
+    use tempfile::TempDir;
+    use uuid::uuid;
+
+    use crate::error::Error;
+    use crate::task::TaskBuilder;
+    use crate::tw::{QueryBuilder, TaskVersion, TaskWarrior};
+
+    // Writing an executable while another test forks can make the exec fail with ETXTBSY, so the
+    // tests which spawn stubs are serialized.
+    static SPAWN_LOCK: Mutex<()> = Mutex::new(());
+
+    /// Write a shell script which is used instead of the real `task` binary into a temporary
+    /// directory
+    ///
+    /// The script can write to the file `$OUT`, which is `out` in the returned directory. The
+    /// returned guard has to be held until the stub was spawned.
+    pub(crate) fn stub(script: &str) -> (MutexGuard<'static, ()>, TempDir, PathBuf) {
+        let lock = SPAWN_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("out");
+        let path = dir.path().join("task-stub");
+        fs::write(
+            &path,
+            format!("#!/bin/sh\nOUT='{}'\n{}\n", out.display(), script),
+        )
+        .unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        (lock, dir, path)
+    }
+
+    #[test]
+    fn test_query_builder() {
+        let query = QueryBuilder::new()
+            .attribute("description", "two words")
+            .attribute_with("project", "not", "work; rm")
+            .tag("home")
+            .without_tag("later");
+        assert_eq!(
+            query.args(),
+            [
+                "description:two words",
+                "project.not:work; rm",
+                "+home",
+                "-later"
+            ]
+        );
+
+        let cmd = query.add_to_cmd(std::process::Command::new("task"));
+        let args: Vec<_> = cmd.get_args().collect();
+        assert_eq!(
+            args,
+            [
+                "description:two words",
+                "project.not:work; rm",
+                "+home",
+                "-later",
+                "export"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_query_builder_custom_binary() {
+        let (_lock, dir, binary) = stub(r#"printf '%s\n' "$@" > "$OUT"; echo '[]'"#);
+        let out = dir.path().join("out");
+
+        let query = QueryBuilder::new().attribute("description", "two words");
+        let tasks = TaskWarrior::new(binary).query_builder(&query).unwrap();
+        assert!(tasks.is_empty());
+        assert_eq!(
+            fs::read_to_string(out).unwrap(),
+            "description:two words\nexport\n"
+        );
+    }
+
+    #[test]
+    fn test_query_custom_binary() {
+        let (_lock, dir, binary) = stub(
+            r#"echo "$@" > "$OUT"
+echo '[{"description":"stub","entry":"20150619T165438Z","status":"pending","uuid":"8ca953d5-18b4-4eb9-bd56-18f2e5b752f0"}]'"#,
+        );
+        let out = dir.path().join("out");
+
+        let tw = TaskWarrior::new(&binary);
+        assert_eq!(tw.binary(), binary.as_path());
+
+        let tasks = tw.query("project:foo +bar").unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].description(), "stub");
+        assert_eq!(
+            fs::read_to_string(out).unwrap().trim(),
+            "project:foo +bar export"
+        );
+    }
+
+    #[test]
+    fn test_save_custom_binary() {
+        let (_lock, dir, binary) = stub("cat > \"$OUT\"");
+        let out = dir.path().join("out");
+
+        let task = TaskBuilder::default()
+            .description("saved by stub")
+            .build()
+            .unwrap();
+        TaskWarrior::new(binary).save(Some(&task)).unwrap();
+
+        let saved = fs::read_to_string(out).unwrap();
+        assert!(saved.starts_with('['));
+        assert!(saved.contains("saved by stub"));
+    }
+
+    #[test]
+    fn test_save_large() {
+        // Fill the stderr pipe before reading stdin, which deadlocks if stdin is written before
+        // stderr is drained
+        let (_lock, dir, binary) = stub("head -c 1000000 /dev/zero >&2\ncat > \"$OUT\"");
+        let out = dir.path().join("out");
+
+        let tasks: Vec<_> = (0..20_000)
+            .map(|i| {
+                TaskBuilder::default()
+                    .description(format!("task {}", i))
+                    .build()
+                    .unwrap()
+            })
+            .collect();
+        TaskWarrior::new(&binary).save(&tasks).unwrap();
+
+        let saved = fs::read_to_string(&out).unwrap();
+        assert!(saved.ends_with(']'));
+        assert!(saved.contains(r#""description":"task 19999""#));
+
+        fs::remove_file(&out).unwrap();
+        let handle = TaskWarrior::new(binary).save_async(tasks).unwrap();
+        handle.wait().unwrap();
+        assert_eq!(fs::read_to_string(&out).unwrap(), saved);
+    }
+
+    #[test]
+    fn test_save_async_stdin_closed() {
+        // Exits successfully without reading the tasks
+        let (_lock, _dir, binary) = stub("exec <&-\nsleep 0.1");
+
+        let tasks: Vec<_> = (0..20_000)
+            .map(|i| {
+                TaskBuilder::default()
+                    .description(format!("task {}", i))
+                    .build()
+                    .unwrap()
+            })
+            .collect();
+        let handle = TaskWarrior::new(binary).save_async(tasks).unwrap();
+        assert!(matches!(handle.wait(), Err(Error::Io(_))));
+    }
+
+    #[test]
+    fn test_env_forwarded() {
+        let (_lock, dir, binary) = stub("echo \"$TASKRC|$TASKDATA|$CUSTOM\" > \"$OUT\"\necho '[]'");
+        let out = dir.path().join("out");
+
+        let tasks = TaskWarrior::new(binary)
+            .taskrc("/some/taskrc")
+            .taskdata("/some/data")
+            .env("CUSTOM", "value")
+            .query("")
+            .unwrap();
+        assert!(tasks.is_empty());
+        assert_eq!(
+            fs::read_to_string(out).unwrap().trim(),
+            "/some/taskrc|/some/data|value"
+        );
+    }
+
+    #[test]
+    fn test_query_failed() {
+        let (_lock, _dir, binary) = stub("echo 'Database is locked' >&2\nexit 3");
+
+        let e = TaskWarrior::new(binary).query("").unwrap_err();
+        assert!(e.to_string().ends_with(": Database is locked"));
+        match e {
+            Error::TaskCmdFailed { status, stderr } => {
+                assert_eq!(status.code(), Some(3));
+                assert_eq!(stderr.trim(), "Database is locked");
+            }
+            other => panic!("Expected TaskCmdFailed, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_save_failed() {
+        let (_lock, _dir, binary) = stub("cat > /dev/null\necho 'Could not import' >&2\nexit 1");
+
+        let task = TaskBuilder::default().description("test").build().unwrap();
+        let saved = TaskWarrior::new(binary).save(Some(&task));
+        assert!(
+            matches!(saved, Err(Error::TaskCmdFailed { ref stderr, .. }) if stderr.trim() == "Could not import")
+        );
+        assert!(saved.unwrap_err().to_string().contains("Could not import"));
+    }
+
+    #[test]
+    fn test_get_task() {
+        let (_lock, dir, binary) = stub(
+            r#"echo "$@" > "$OUT"
+echo '[{"description":"stub","entry":"20150619T165438Z","status":"pending","uuid":"8ca953d5-18b4-4eb9-bd56-18f2e5b752f0"}]'"#,
+        );
+        let out = dir.path().join("out");
+
+        let uuid = uuid!("8ca953d5-18b4-4eb9-bd56-18f2e5b752f0");
+        let task = TaskWarrior::new(binary).get_task(&uuid).unwrap();
+        assert_eq!(task.map(|t| *t.uuid()), Some(uuid));
+        assert_eq!(
+            fs::read_to_string(out).unwrap().trim(),
+            "8ca953d5-18b4-4eb9-bd56-18f2e5b752f0 export"
+        );
+    }
+
+    #[test]
+    fn test_get_task_missing() {
+        let (_lock, _dir, binary) = stub("echo '[]'");
+
+        let uuid = uuid!("8ca953d5-18b4-4eb9-bd56-18f2e5b752f0");
+        assert!(TaskWarrior::new(binary).get_task(&uuid).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_count() {
+        let (_lock, dir, binary) = stub("echo \"$@\" > \"$OUT\"\necho 7");
+        let out = dir.path().join("out");
+
+        assert_eq!(TaskWarrior::new(binary).count("+work").unwrap(), 7);
+        assert_eq!(fs::read_to_string(out).unwrap().trim(), "+work count");
+    }
+
+    #[test]
+    fn test_count_garbage() {
+        let (_lock, _dir, binary) = stub("echo seven");
+
+        let count = TaskWarrior::new(binary).count("");
+        assert!(matches!(count, Err(Error::UnexpectedOutput(_))));
+    }
+
+    #[test]
+    fn test_modify() {
+        let (_lock, dir, binary) = stub("printf '%s\\n' \"$@\" > \"$OUT\"");
+        let out = dir.path().join("out");
+
+        TaskWarrior::new(binary)
+            .modify("project:foo +bar", &["priority:H", "description:two words"])
+            .unwrap();
+        assert_eq!(
+            fs::read_to_string(out).unwrap(),
+            "project:foo\n+bar\nmodify\npriority:H\ndescription:two words\n"
+        );
+    }
+
+    #[test]
+    fn test_modify_failed() {
+        let (_lock, _dir, binary) = stub("echo 'No tasks specified.' >&2\nexit 1");
+
+        let modified = TaskWarrior::new(binary).modify("", &["+foo"]);
+        assert!(matches!(modified, Err(Error::TaskCmdFailed { .. })));
+    }
+
+    #[test]
+    fn test_add() {
+        let (_lock, dir, binary) = stub(
+            r#"case "$1" in
+add) echo "$@" > "$OUT" ;;
+*) echo '[{"description":"new task","entry":"20150619T165438Z","status":"pending","uuid":"8ca953d5-18b4-4eb9-bd56-18f2e5b752f0"}]' ;;
+esac"#,
+        );
+        let out = dir.path().join("out");
+
+        let uuid = TaskWarrior::new(binary)
+            .add("new task", &["project:foo"])
+            .unwrap();
+        assert_eq!(uuid, uuid!("8ca953d5-18b4-4eb9-bd56-18f2e5b752f0"));
+        assert_eq!(
+            fs::read_to_string(out).unwrap().trim(),
+            "add project:foo -- new task"
+        );
+    }
+
+    #[test]
+    fn test_add_failed() {
+        let (_lock, _dir, binary) = stub("echo 'Cannot add' >&2\nexit 2");
+
+        let added = TaskWarrior::new(binary).add("new task", &[]);
+        assert!(matches!(added, Err(Error::TaskCmdFailed { .. })));
+    }
+
+    #[test]
+    fn test_done() {
+        let (_lock, dir, binary) = stub("echo \"$@\" > \"$OUT\"");
+        let out = dir.path().join("out");
+
+        TaskWarrior::new(binary).done("project:foo +bar").unwrap();
+        assert_eq!(
+            fs::read_to_string(out).unwrap().trim(),
+            "project:foo +bar done"
+        );
+    }
+
+    #[test]
+    fn test_delete() {
+        let (_lock, dir, binary) = stub("echo \"$@\" > \"$OUT\"");
+        let out = dir.path().join("out");
+
+        TaskWarrior::new(binary).delete("42").unwrap();
+        assert_eq!(
+            fs::read_to_string(out).unwrap().trim(),
+            "rc.confirmation=off 42 delete"
+        );
+    }
+
+    #[test]
+    fn test_parse_version() {
+        let v: TaskVersion = "2.5.3\n".parse().unwrap();
+        assert_eq!((v.major, v.minor, v.patch), (2, 5, 3));
+        assert!(!v.has_depends_array());
+
+        let v: TaskVersion = "2.6.2".parse().unwrap();
+        assert_eq!(v.to_string(), "2.6.2");
+        assert!(v.has_depends_array());
+
+        let v: TaskVersion = "3.0.0-beta".parse().unwrap();
+        assert_eq!(v.to_string(), "3.0.0");
+        assert!(v.has_depends_array());
+
+        assert!(matches!(
+            "task: command not found".parse::<TaskVersion>(),
+            Err(Error::UnexpectedOutput(_))
+        ));
+        assert!("".parse::<TaskVersion>().is_err());
+        assert!("2".parse::<TaskVersion>().is_err());
+    }
+
+    #[test]
+    fn test_version() {
+        let (_lock, _dir, binary) = stub("[ \"$1\" = --version ] && echo 2.5.3");
+
+        let version = TaskWarrior::new(binary).version().unwrap();
+        assert_eq!(version.to_string(), "2.5.3");
+    }
 }
@@ -0,0 +1,149 @@
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+
+//! Async variants of the functions in the `tw` module, which do not block the executor while the
+//! `task` binary runs. They have to be called from within a tokio runtime.
+//!
+//! This module is only available with the `tokio` feature.
+
+use std::process::Stdio;
+
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use crate::error::Error;
+use crate::import::import;
+use crate::task::Task;
+use crate::tw::{add_query_to_cmd, check_output, QueryBuilder, TaskWarrior};
+
+/// This will give you all tasks which match the given query in the taskwarrior query syntax.
+/// This is not sanitized. Never get the query string from an untrusted user.
+pub async fn query(query: &str) -> Result<Vec<Task>, Error> {
+    query_with(&TaskWarrior::default(), query).await
+}
+
+/// Like `query()`, but calls the given taskwarrior installation
+pub async fn query_with(tw: &TaskWarrior, query: &str) -> Result<Vec<Task>, Error> {
+    run_query_cmd(add_query_to_cmd(query, tw.command())).await
+}
+
+/// This will give you all tasks which match the query built with the `QueryBuilder`.
+pub async fn query_builder(query: &QueryBuilder) -> Result<Vec<Task>, Error> {
+    query_builder_with(&TaskWarrior::default(), query).await
+}
+
+/// Like `query_builder()`, but calls the given taskwarrior installation
+pub async fn query_builder_with(
+    tw: &TaskWarrior,
+    query: &QueryBuilder,
+) -> Result<Vec<Task>, Error> {
+    run_query_cmd(query.add_to_cmd(tw.command())).await
+}
+
+/// Run the export Command and import the tasks it prints
+async fn run_query_cmd(cmd: std::process::Command) -> Result<Vec<Task>, Error> {
+    let mut cmd = Command::from(cmd);
+    cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+    let output = check_output(cmd.output().await?)?;
+    import(&output.stdout[..])
+}
+
+/// This will save the given tasks to taskwarrior. Call with `Some(&task)` if you just have one
+/// task.
+pub async fn save<'a, T>(tasks: T) -> Result<(), Error>
+where
+    T: IntoIterator<Item = &'a Task>,
+{
+    save_with(&TaskWarrior::default(), tasks).await
+}
+
+/// Like `save()`, but calls the given taskwarrior installation
+pub async fn save_with<'a, T>(tw: &TaskWarrior, tasks: T) -> Result<(), Error>
+where
+    T: IntoIterator<Item = &'a Task>,
+{
+    let tasks: Vec<&Task> = tasks.into_iter().collect();
+    let input_buffer = serde_json::to_vec(&tasks).map_err(Error::SerializeError)?;
+
+    let mut cmd = Command::from(tw.import_command());
+    cmd.stderr(Stdio::piped());
+    let mut import = cmd.spawn()?;
+    let mut stdin = import.stdin.take().ok_or(Error::TaskCmdError)?;
+    // The output is read while writing, so neither side blocks on a full pipe
+    let write = async move {
+        // Dropping stdin closes it, so taskwarrior stops reading
+        stdin.write_all(&input_buffer).await
+    };
+    let (written, output) = tokio::join!(write, import.wait_with_output());
+    check_output(output?)?;
+    written.map_err(Error::from)
+}
+
+#[cfg(all(test, unix))]
+mod test {
+    use std::fs;
+
+    use crate::task::TaskBuilder;
+    use crate::tw::test::stub;
+    use crate::tw::{QueryBuilder, TaskWarrior};
+    use crate::tw_async::{query_builder_with, query_with, save_with};
+
+    // The lock has to be held until the stub was spawned
+    #[allow(clippy::await_holding_lock)]
+    #[tokio::test]
+    async fn test_query_with() {
+        let (_lock, dir, binary) = stub(
+            r#"echo "$@" > "$OUT"
+echo '[{"description":"stub","entry":"20150619T165438Z","status":"pending","uuid":"8ca953d5-18b4-4eb9-bd56-18f2e5b752f0"}]'"#,
+        );
+        let out = dir.path().join("out");
+
+        let tasks = query_with(&TaskWarrior::new(binary), "+work")
+            .await
+            .unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].description(), "stub");
+        assert_eq!(fs::read_to_string(out).unwrap().trim(), "+work export");
+    }
+
+    // The lock has to be held until the stub was spawned
+    #[allow(clippy::await_holding_lock)]
+    #[tokio::test]
+    async fn test_query_builder_with() {
+        let (_lock, dir, binary) = stub(r#"printf '%s\n' "$@" > "$OUT"; echo '[]'"#);
+        let out = dir.path().join("out");
+
+        let query = QueryBuilder::new().attribute("description", "two words");
+        let tasks = query_builder_with(&TaskWarrior::new(binary), &query)
+            .await
+            .unwrap();
+        assert!(tasks.is_empty());
+        assert_eq!(
+            fs::read_to_string(out).unwrap(),
+            "description:two words\nexport\n"
+        );
+    }
+
+    // The lock has to be held until the stub was spawned
+    #[allow(clippy::await_holding_lock)]
+    #[tokio::test]
+    async fn test_save_with() {
+        let (_lock, dir, binary) = stub("cat > \"$OUT\"");
+        let out = dir.path().join("out");
+
+        let task = TaskBuilder::default()
+            .description("saved by stub")
+            .build()
+            .unwrap();
+        save_with(&TaskWarrior::new(binary), Some(&task))
+            .await
+            .unwrap();
+
+        let saved = fs::read_to_string(out).unwrap();
+        assert!(saved.starts_with('['));
+        assert!(saved.contains("saved by stub"));
+    }
+}
@@ -15,7 +15,10 @@ use serde::Serializer;
 pub type UDAName = String;
 
 /// A UDA can have different value types.
+///
+/// New variants may be added in minor releases.
 #[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[non_exhaustive]
 pub enum UDAValue {
     /// UDA is a string
     Str(String),
@@ -25,6 +28,29 @@ pub enum UDAValue {
     F64(f64),
 }
 
+impl UDAValue {
+    /// Get the numeric value of the UDA, regardless of whether it is an integer or a float
+    ///
+    /// Strings are not parsed, `None` is returned for them even if they contain a number.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            UDAValue::Str(_) => None,
+            UDAValue::U64(n) => Some(*n as f64),
+            UDAValue::F64(n) => Some(*n),
+        }
+    }
+}
+
+impl fmt::Display for UDAValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            UDAValue::Str(s) => write!(f, "{}", s),
+            UDAValue::U64(n) => write!(f, "{}", n),
+            UDAValue::F64(n) => write!(f, "{}", n),
+        }
+    }
+}
+
 impl Serialize for UDAValue {
     fn serialize<S>(&self, serializer: S) -> RResult<S::Ok, S::Error>
     where
@@ -33,11 +59,27 @@ impl Serialize for UDAValue {
         match self {
             UDAValue::Str(ref s) => s.serialize(serializer),
             UDAValue::U64(s) => s.serialize(serializer),
-            UDAValue::F64(s) => s.serialize(serializer),
+            UDAValue::F64(s) => serialize_float(*s, serializer),
         }
     }
 }
 
+/// Serialize a float, as integer if it is whole-valued and the `integral-floats` feature is
+/// enabled, like taskwarrior does
+pub(crate) fn serialize_float<S>(value: f64, serializer: S) -> RResult<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    // Larger values can not be converted to integers exactly
+    const MAX_EXACT: f64 = 9_007_199_254_740_992.0;
+
+    if cfg!(feature = "integral-floats") && value.fract() == 0.0 && value.abs() <= MAX_EXACT {
+        serializer.serialize_i64(value as i64)
+    } else {
+        serializer.serialize_f64(value)
+    }
+}
+
 struct UDAVisitor;
 
 impl<'de> Visitor<'de> for UDAVisitor {
@@ -52,6 +94,13 @@ impl<'de> Visitor<'de> for UDAVisitor {
     {
         Ok(UDAValue::U64(value))
     }
+    // Negative whole-valued floats are written as integers with the `integral-floats` feature
+    fn visit_i64<E>(self, value: i64) -> Result<UDAValue, E>
+    where
+        E: de::Error,
+    {
+        Ok(UDAValue::F64(value as f64))
+    }
     fn visit_f64<E>(self, value: f64) -> Result<UDAValue, E>
     where
         E: de::Error,
@@ -78,3 +127,59 @@ impl<'de> Deserialize<'de> for UDAValue {
 /// The UDA Type is just a BTreeMap<UDAName, UDAValue> in which all fields of a task are saved,
 /// which are not part of the taskwarrior standard. (This makes them user defined attributes.)
 pub type UDA = BTreeMap<UDAName, UDAValue>;
+
+#[cfg(test)]
+mod test {
+    use crate::uda::UDAValue;
+
+    #[test]
+    fn test_as_f64() {
+        assert_eq!(UDAValue::U64(3).as_f64(), Some(3.0));
+        assert_eq!(UDAValue::F64(2.5).as_f64(), Some(2.5));
+        assert_eq!(UDAValue::Str("3".to_owned()).as_f64(), None);
+    }
+
+    #[test]
+    fn test_serialize_float() {
+        let whole = serde_json::to_string(&UDAValue::F64(5.0)).unwrap();
+        if cfg!(feature = "integral-floats") {
+            assert_eq!(whole, "5");
+        } else {
+            assert_eq!(whole, "5.0");
+        }
+        assert_eq!(serde_json::to_string(&UDAValue::F64(5.5)).unwrap(), "5.5");
+
+        // Too large to be written as integer exactly
+        let large = serde_json::to_string(&UDAValue::F64(1e20)).unwrap();
+        assert_eq!(
+            serde_json::from_str::<UDAValue>(&large).unwrap(),
+            UDAValue::F64(1e20)
+        );
+    }
+
+    #[test]
+    fn test_negative_integer() {
+        assert_eq!(
+            serde_json::from_str::<UDAValue>("-5").unwrap(),
+            UDAValue::F64(-5.0)
+        );
+    }
+
+    #[cfg(feature = "integral-floats")]
+    #[test]
+    fn test_negative_whole_float_roundtrip() {
+        use crate::export::export;
+        use crate::import::import;
+        use crate::task::{Task, TW26};
+
+        let mut task: Task<TW26> = Task::builder().description("d").build().unwrap();
+        task.uda_mut().insert("offset".into(), UDAValue::F64(-5.0));
+
+        let mut out = Vec::new();
+        export(&[task], &mut out).unwrap();
+        assert!(String::from_utf8_lossy(&out).contains(r#""offset":-5"#));
+
+        let imported: Vec<Task<TW26>> = import(out.as_slice()).unwrap();
+        assert_eq!(imported[0].uda()["offset"], UDAValue::F64(-5.0));
+    }
+}
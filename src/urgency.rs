@@ -4,7 +4,371 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 //
 
-//! Module containing `Urgency` type
+//! Module containing `Urgency` type and the urgency computation
 
-/// type definition for Urgency
-pub type Urgency = f64;
+use std::cmp::Ordering;
+use std::fmt;
+
+use serde::de::{Error as DeError, Unexpected, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::date::Date;
+use crate::error::Error;
+use crate::status::TaskStatus;
+use crate::store::TaskStore;
+use crate::task::{Task, TaskWarriorVersion};
+use crate::uda::serialize_float;
+
+/// The urgency of a task, which is always a finite number
+///
+/// `Urgency::new()` rejects values which are not finite, while the conversion from `f64` clamps
+/// them: infinite values become the largest finite value with the same sign, `NaN` becomes zero.
+/// Deserializing rejects values which are not finite.
+///
+/// It is displayed with two decimals by default, a precision can be given with `{:.N}`.
+/// With the `integral-floats` feature, whole-valued urgencies are serialized as integers.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub struct Urgency(f64);
+
+impl Urgency {
+    /// Create a new Urgency, failing with `Error::InvalidUrgency` if the value is not finite
+    pub fn new(value: f64) -> Result<Urgency, Error> {
+        if value.is_finite() {
+            Ok(Urgency(value))
+        } else {
+            Err(Error::InvalidUrgency(value))
+        }
+    }
+
+    /// Get the urgency as number
+    pub fn value(&self) -> f64 {
+        self.0
+    }
+}
+
+impl Serialize for Urgency {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_float(self.0, serializer)
+    }
+}
+
+impl From<f64> for Urgency {
+    fn from(value: f64) -> Urgency {
+        if value.is_nan() {
+            Urgency(0.0)
+        } else {
+            Urgency(value.clamp(f64::MIN, f64::MAX))
+        }
+    }
+}
+
+impl From<Urgency> for f64 {
+    fn from(urgency: Urgency) -> f64 {
+        urgency.0
+    }
+}
+
+impl PartialEq<f64> for Urgency {
+    fn eq(&self, other: &f64) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialOrd<f64> for Urgency {
+    fn partial_cmp(&self, other: &f64) -> Option<Ordering> {
+        self.0.partial_cmp(other)
+    }
+}
+
+impl fmt::Display for Urgency {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:.*}", f.precision().unwrap_or(2), self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Urgency {
+    fn deserialize<D>(deserializer: D) -> Result<Urgency, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct UrgencyVisitor;
+
+        impl<'de> Visitor<'de> for UrgencyVisitor {
+            type Value = f64;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a number or a string containing a number")
+            }
+
+            fn visit_f64<E: DeError>(self, v: f64) -> Result<f64, E> {
+                Ok(v)
+            }
+
+            fn visit_i64<E: DeError>(self, v: i64) -> Result<f64, E> {
+                Ok(v as f64)
+            }
+
+            fn visit_u64<E: DeError>(self, v: u64) -> Result<f64, E> {
+                Ok(v as f64)
+            }
+
+            // Some exports quote the urgency, taskwarrior accepts that on import as well
+            fn visit_str<E: DeError>(self, v: &str) -> Result<f64, E> {
+                v.trim()
+                    .parse()
+                    .map_err(|_| E::invalid_value(Unexpected::Str(v), &self))
+            }
+        }
+
+        let value = deserializer.deserialize_any(UrgencyVisitor)?;
+        Urgency::new(value).map_err(D::Error::custom)
+    }
+}
+
+/// Sort tasks by their stored urgency, the most urgent first
+///
+/// Tasks without urgency come last. The sort is stable, so tasks with the same urgency keep their
+/// order. The urgency is not recomputed, see `Task::urgency_computed()` for that.
+pub fn sort_by_urgency<V: TaskWarriorVersion>(tasks: &mut [Task<V>]) {
+    tasks.sort_by(|a, b| match (a.urgency(), b.urgency()) {
+        (Some(a), Some(b)) => b.value().total_cmp(&a.value()),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    });
+}
+
+/// The coefficients of the terms of the urgency formula, see `Task::urgency_computed()`.
+///
+/// The defaults are the defaults of taskwarrior, the names of the fields match the
+/// `urgency.<name>.coefficient` settings.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UrgencyCoefficients {
+    /// Coefficient for the `next` tag
+    pub next: f64,
+    /// Coefficient for the proximity of the due date
+    pub due: f64,
+    /// Coefficient for a `H` priority
+    pub priority_high: f64,
+    /// Coefficient for a `M` priority
+    pub priority_medium: f64,
+    /// Coefficient for a `L` priority
+    pub priority_low: f64,
+    /// Coefficient for a scheduled date in the past
+    pub scheduled: f64,
+    /// Coefficient for started tasks
+    pub active: f64,
+    /// Coefficient for the age of the task
+    pub age: f64,
+    /// The age in days at which the age term reaches its maximum
+    pub age_max: f64,
+    /// Coefficient for annotations
+    pub annotations: f64,
+    /// Coefficient for tags
+    pub tags: f64,
+    /// Coefficient for having a project
+    pub project: f64,
+    /// Coefficient for waiting tasks
+    pub waiting: f64,
+    /// Coefficient for tasks which depend on other pending tasks
+    pub blocked: f64,
+    /// Coefficient for tasks which other pending tasks depend on
+    pub blocking: f64,
+}
+
+impl Default for UrgencyCoefficients {
+    fn default() -> Self {
+        UrgencyCoefficients {
+            next: 15.0,
+            due: 12.0,
+            priority_high: 6.0,
+            priority_medium: 3.9,
+            priority_low: 1.8,
+            scheduled: 5.0,
+            active: 4.0,
+            age: 2.0,
+            age_max: 365.0,
+            annotations: 1.0,
+            tags: 1.0,
+            project: 1.0,
+            waiting: -3.0,
+            blocked: -5.0,
+            blocking: 8.0,
+        }
+    }
+}
+
+const SECONDS_PER_DAY: f64 = 86400.0;
+
+impl UrgencyCoefficients {
+    /// Compute the urgency of the task at the time `now`, like taskwarrior does
+    ///
+    /// The blocked and blocking terms are computed from the tasks in `store`. Without a store, all
+    /// dependencies are assumed to be pending and the blocking term is left out.
+    pub(crate) fn urgency<V: TaskWarriorVersion>(
+        &self,
+        task: &Task<V>,
+        store: Option<&TaskStore<V>>,
+        now: &Date,
+    ) -> f64 {
+        let mut urgency = 0.0;
+
+        if task.tags().into_iter().flatten().any(|t| t == "next") {
+            urgency += self.next;
+        }
+        urgency += self.due * due_term(task.due(), now);
+        urgency += match task.priority().map(String::as_str) {
+            Some("H") => self.priority_high,
+            Some("M") => self.priority_medium,
+            Some("L") => self.priority_low,
+            _ => 0.0,
+        };
+        if matches!(task.scheduled(), Some(scheduled) if scheduled < now) {
+            urgency += self.scheduled;
+        }
+        if task.start().is_some() {
+            urgency += self.active;
+        }
+        urgency += self.age * self.age_term(task.entry(), now);
+        urgency += self.annotations * count_term(task.annotations().map_or(0, Vec::len));
+        urgency += self.tags * count_term(task.tags().map_or(0, Vec::len));
+        if task.project().is_some() {
+            urgency += self.project;
+        }
+        if *task.status() == TaskStatus::Waiting {
+            urgency += self.waiting;
+        }
+        let blocked = match store {
+            Some(store) => task.is_blocked(store),
+            None => task.depends().map_or(0, Vec::len) > 0,
+        };
+        if blocked {
+            urgency += self.blocked;
+        }
+        if matches!(store, Some(store) if is_blocking(task, store)) {
+            urgency += self.blocking;
+        }
+
+        urgency
+    }
+
+    fn age_term(&self, entry: &Date, now: &Date) -> f64 {
+        // Taskwarrior uses the age in whole days
+        let age = (**now - **entry).num_days() as f64;
+        if self.age_max == 0.0 || age > self.age_max {
+            1.0
+        } else {
+            age / self.age_max
+        }
+    }
+}
+
+/// Check whether a task in `store` which is not completed or deleted depends on the task
+fn is_blocking<V: TaskWarriorVersion>(task: &Task<V>, store: &TaskStore<V>) -> bool {
+    store.iter().any(|other| {
+        !other.status().is_terminal()
+            && other
+                .depends()
+                .into_iter()
+                .flatten()
+                .any(|u| u == task.uuid())
+    })
+}
+
+/// Maps the range from 14 days before to 7 days after the due date to 0.2 - 1.0
+fn due_term(due: Option<&Date>, now: &Date) -> f64 {
+    match due {
+        None => 0.0,
+        Some(due) => {
+            let days_overdue = (**now - **due).num_seconds() as f64 / SECONDS_PER_DAY;
+            if days_overdue >= 7.0 {
+                1.0
+            } else if days_overdue >= -14.0 {
+                ((days_overdue + 14.0) * 0.8 / 21.0) + 0.2
+            } else {
+                0.2
+            }
+        }
+    }
+}
+
+/// Term for the number of annotations or tags
+fn count_term(count: usize) -> f64 {
+    match count {
+        0 => 0.0,
+        1 => 0.8,
+        2 => 0.9,
+        _ => 1.0,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::error::Error;
+    use crate::task::Task;
+    use crate::urgency::{sort_by_urgency, Urgency};
+
+    #[test]
+    fn test_sort_by_urgency() {
+        let mut tasks: Vec<Task> = [Some(1.5), None, Some(8.0), Some(-2.0)]
+            .iter()
+            .map(|urgency| {
+                let mut task = Task::builder().description("d").build().unwrap();
+                task.set_urgency(urgency.map(Urgency::from));
+                task
+            })
+            .collect();
+
+        sort_by_urgency(&mut tasks);
+        let urgencies: Vec<Option<f64>> = tasks
+            .iter()
+            .map(|t| t.urgency().map(|u| u.value()))
+            .collect();
+        assert_eq!(urgencies, [Some(8.0), Some(1.5), Some(-2.0), None]);
+    }
+
+    #[test]
+    fn test_urgency() {
+        let urgency: Urgency = serde_json::from_str("5.3").unwrap();
+        assert_eq!(urgency, 5.3);
+        assert!(urgency > 5.0);
+        assert_eq!(urgency.to_string(), "5.30");
+        assert_eq!(format!("{:.1}", urgency), "5.3");
+        assert_eq!(serde_json::to_string(&urgency).unwrap(), "5.3");
+
+        let whole = serde_json::to_string(&Urgency::from(5.0)).unwrap();
+        if cfg!(feature = "integral-floats") {
+            assert_eq!(whole, "5");
+        } else {
+            assert_eq!(whole, "5.0");
+        }
+    }
+
+    #[test]
+    fn test_urgency_from_string() {
+        let urgency: Urgency = serde_json::from_str(r#""5.3""#).unwrap();
+        assert_eq!(urgency, 5.3);
+        assert_eq!(serde_json::from_str::<Urgency>("-2").unwrap(), -2.0);
+        assert!(serde_json::from_str::<Urgency>(r#""high""#).is_err());
+
+        let task: Task = serde_json::from_str(
+            r#"{"description":"d","entry":"20150619T165438Z","status":"pending","uuid":"8ca953d5-18b4-4eb9-bd56-18f2e5b752f0","urgency":"5.3"}"#,
+        )
+        .unwrap();
+        assert_eq!(task.urgency(), Some(&Urgency::from(5.3)));
+    }
+
+    #[test]
+    fn test_urgency_not_finite() {
+        assert!(matches!(
+            Urgency::new(f64::NAN),
+            Err(Error::InvalidUrgency(u)) if u.is_nan()
+        ));
+        assert!(Urgency::new(f64::INFINITY).is_err());
+        assert!(serde_json::from_str::<Urgency>("1e400").is_err());
+        assert!(serde_json::from_str::<Urgency>(r#""NaN""#).is_err());
+
+        assert_eq!(Urgency::from(f64::NAN), 0.0);
+        assert_eq!(Urgency::from(f64::NEG_INFINITY), f64::MIN);
+    }
+}